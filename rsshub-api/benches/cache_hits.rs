@@ -0,0 +1,115 @@
+//! Cost of cache hits on `/api/namespace`, alone and under concurrent `search_routes`
+//! style tool calls, against the previous approach of decoding the cached JSON per hit.
+
+use std::{
+    hint::black_box,
+    sync::{Arc, Mutex},
+};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rsshub_api::{NamespaceResp, RsshubApiClient};
+
+const CONCURRENT_CALLS: usize = 16;
+
+/// `/api/namespace` body with about 1000 namespaces, built from the category fixture.
+fn namespaces_body() -> String {
+    let categories: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(include_str!("../tests/category.json")).unwrap();
+    let mut namespaces = serde_json::Map::new();
+    for copy in 0..5 {
+        for (name, info) in &categories {
+            namespaces.insert(
+                format!("{name}-{copy}"),
+                serde_json::json!({ "routes": info["routes"] }),
+            );
+        }
+    }
+    serde_json::Value::Object(namespaces).to_string()
+}
+
+/// The scan `search_routes` runs over all namespaces.
+fn search_routes(namespaces: &NamespaceResp, query: &str) -> usize {
+    namespaces
+        .values()
+        .filter_map(|ns| ns.routes.as_ref())
+        .flatten()
+        .filter(|(key, details)| key.contains(query) || details.name.contains(query))
+        .count()
+}
+
+fn bench_namespace_hits(c: &mut Criterion) {
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let body = namespaces_body();
+    let (_server, client) = rt.block_on(async {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/api/namespace")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(&body)
+            .create_async()
+            .await;
+        let client = RsshubApiClient::builder()
+            .host(server.url())
+            .build()
+            .unwrap();
+        client.get_all_namespaces().await.unwrap();
+        (server, client)
+    });
+    // How hits were served before: clone the cached JSON under a lock, then decode it
+    let json: Arc<Mutex<serde_json::Value>> =
+        Arc::new(Mutex::new(serde_json::from_str(&body).unwrap()));
+    let decode_hit = |json: &Mutex<serde_json::Value>| {
+        let value = json.lock().unwrap().clone();
+        serde_json::from_value::<NamespaceResp>(value).unwrap()
+    };
+
+    let mut group = c.benchmark_group("all_namespaces_hit");
+    group.bench_function("json_clone_and_decode", |b| {
+        b.iter(|| black_box(decode_hit(&json)))
+    });
+    group.bench_function("shared_arc", |b| {
+        b.to_async(&rt)
+            .iter(|| async { black_box(client.get_all_namespaces().await.unwrap()) })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("concurrent_search_routes");
+    group.sample_size(20);
+    group.bench_function("json_clone_and_decode", |b| {
+        b.to_async(&rt).iter(|| async {
+            let tasks: Vec<_> = (0..CONCURRENT_CALLS)
+                .map(|_| {
+                    let json = json.clone();
+                    tokio::spawn(async move { search_routes(&decode_hit(&json), "hot") })
+                })
+                .collect();
+            for task in tasks {
+                black_box(task.await.unwrap());
+            }
+        })
+    });
+    group.bench_function("shared_arc", |b| {
+        b.to_async(&rt).iter(|| async {
+            let tasks: Vec<_> = (0..CONCURRENT_CALLS)
+                .map(|_| {
+                    let client = client.clone();
+                    tokio::spawn(async move {
+                        let namespaces = client.get_all_namespaces().await.unwrap();
+                        search_routes(&namespaces, "hot")
+                    })
+                })
+                .collect();
+            for task in tasks {
+                black_box(task.await.unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_namespace_hits);
+criterion_main!(benches);
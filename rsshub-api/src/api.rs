@@ -0,0 +1,66 @@
+//! Backend-agnostic interface to RSSHub data.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    error::Result, CategoryItems, FeedQuery, FeedResponse, Fetched, NamespaceResp, RoutesMap,
+    RsshubApiClient, RulesInfo, RulesResp,
+};
+
+/// The RSSHub data used by tools: namespaces, radar rules, categories and feeds.
+///
+/// [`RsshubApiClient`] implements it over HTTP; code written against the trait can run
+/// against other backends too, such as the in-memory `FakeRsshub` (feature `fake`).
+#[async_trait]
+pub trait RsshubApi: Send + Sync {
+    async fn fetch_all_namespaces(&self) -> Result<Fetched<Arc<NamespaceResp>>>;
+
+    async fn fetch_namespace(&self, namespace: &str) -> Result<Fetched<RoutesMap>>;
+
+    async fn fetch_all_radar_rules(&self) -> Result<Fetched<Arc<RulesResp>>>;
+
+    async fn fetch_radar_rule(&self, domain: &str) -> Result<Fetched<RulesInfo>>;
+
+    async fn fetch_category(&self, category: &str) -> Result<Fetched<CategoryItems>>;
+
+    /// Fetch the feed at an RSSHub route path, e.g. `github/issue/DIYgod/RSSHub`
+    async fn fetch_feed(&self, path: &str) -> Result<Fetched<FeedResponse>>;
+
+    /// Fetch the feed at `path` with RSSHub's generic parameters appended
+    async fn fetch_feed_with(
+        &self,
+        path: &str,
+        query: &FeedQuery,
+    ) -> Result<Fetched<FeedResponse>> {
+        self.fetch_feed(&query.apply(path)).await
+    }
+}
+
+#[async_trait]
+impl RsshubApi for RsshubApiClient {
+    async fn fetch_all_namespaces(&self) -> Result<Fetched<Arc<NamespaceResp>>> {
+        RsshubApiClient::fetch_all_namespaces(self).await
+    }
+
+    async fn fetch_namespace(&self, namespace: &str) -> Result<Fetched<RoutesMap>> {
+        RsshubApiClient::fetch_namespace(self, namespace).await
+    }
+
+    async fn fetch_all_radar_rules(&self) -> Result<Fetched<Arc<RulesResp>>> {
+        RsshubApiClient::fetch_all_radar_rules(self).await
+    }
+
+    async fn fetch_radar_rule(&self, domain: &str) -> Result<Fetched<RulesInfo>> {
+        RsshubApiClient::fetch_radar_rule(self, domain).await
+    }
+
+    async fn fetch_category(&self, category: &str) -> Result<Fetched<CategoryItems>> {
+        RsshubApiClient::fetch_category(self, category).await
+    }
+
+    async fn fetch_feed(&self, path: &str) -> Result<Fetched<FeedResponse>> {
+        RsshubApiClient::fetch_feed(self, path).await
+    }
+}
@@ -0,0 +1,100 @@
+//! Mapping of Atom feeds into [`FeedResponse`].
+
+use atom_syndication::{Entry, Feed};
+
+use crate::{media, Enclosure, FeedChannel, FeedItem, FeedResponse};
+
+/// Whether `content` looks like an Atom document rather than RSS or HTML.
+pub(crate) fn is_atom(content: &str) -> bool {
+    content.contains("<feed") && content.contains("http://www.w3.org/2005/Atom")
+}
+
+pub(crate) fn parse_atom(content: &str) -> Result<FeedResponse, atom_syndication::Error> {
+    let feed = Feed::read_from(content.as_bytes())?;
+    Ok(FeedResponse {
+        title: feed.title().as_str().to_string(),
+        description: feed
+            .subtitle()
+            .map(|s| s.as_str().to_string())
+            .unwrap_or_default(),
+        channel: feed_channel(&feed),
+        items: feed.entries().iter().map(feed_item).collect(),
+        raw_content: Some(content.to_string()),
+        warnings: Vec::new(),
+    })
+}
+
+/// The logo is preferred over the icon as the image.
+fn feed_channel(feed: &Feed) -> FeedChannel {
+    let with_rel = |rel: &str| {
+        feed.links()
+            .iter()
+            .find(|l| l.rel() == rel)
+            .map(|l| l.href().to_string())
+    };
+    FeedChannel {
+        link: with_rel("alternate"),
+        self_link: with_rel("self"),
+        language: feed.lang().map(|l| l.to_string()),
+        image: feed.logo().or(feed.icon()).map(|i| i.to_string()),
+        last_build_date: Some(feed.updated())
+            .filter(|d| d.timestamp() != 0)
+            .map(|d| d.to_rfc3339()),
+        generator: feed.generator().map(|g| g.value().trim().to_string()),
+        ..FeedChannel::default()
+    }
+}
+
+/// The entry's `alternate` link is its link, `enclosure` links are its enclosures and
+/// `replies` its comments; the full content is preferred over the summary, and the
+/// publication date falls back to the last update.
+fn feed_item(entry: &Entry) -> FeedItem {
+    let with_rel = |rel: &str| entry.links().iter().find(|l| l.rel() == rel);
+    let link = with_rel("alternate")
+        .or_else(|| entry.links().first())
+        .map(|l| l.href().to_string())
+        .unwrap_or_default();
+    let description = entry
+        .content()
+        .and_then(|c| c.value())
+        .or_else(|| entry.summary().map(|s| s.as_str()))
+        .unwrap_or_default()
+        .to_string();
+    let authors: Vec<&str> = entry.authors().iter().map(|a| a.name()).collect();
+    // atom_syndication fills a missing `<updated>` with the Unix epoch
+    let updated = Some(entry.updated())
+        .filter(|d| d.timestamp() != 0)
+        .map(|d| d.to_rfc3339());
+    let (image, thumbnail) = media::media_images(entry.extensions().get("media"));
+    media::complete(FeedItem {
+        title: entry.title().as_str().to_string(),
+        description,
+        link,
+        pub_date: entry
+            .published()
+            .map(|d| d.to_rfc3339())
+            .or_else(|| updated.clone()),
+        updated,
+        author: (!authors.is_empty()).then(|| authors.join(", ")),
+        categories: entry
+            .categories()
+            .iter()
+            .map(|c| c.label().unwrap_or(c.term()).to_string())
+            .collect(),
+        guid: Some(entry.id().to_string()).filter(|id| !id.is_empty()),
+        comments: with_rel("replies").map(|l| l.href().to_string()),
+        enclosures: entry
+            .links()
+            .iter()
+            .filter(|l| l.rel() == "enclosure")
+            .map(|l| Enclosure {
+                url: l.href().to_string(),
+                mime_type: l.mime_type().map(|t| t.to_string()),
+                length: l.length().and_then(|len| len.trim().parse().ok()),
+            })
+            .collect(),
+        image,
+        thumbnail,
+        ..FeedItem::default()
+    })
+}
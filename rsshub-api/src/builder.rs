@@ -0,0 +1,501 @@
+//! Validated construction of [`RsshubApiClient`].
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::{FixedOffset, Offset, Utc};
+
+use crate::{
+    auth::{redact_url, AuthConfig},
+    cache::{CacheBackend, CacheConfig, MemoryCache},
+    error::{Result, RsshubError},
+    hosts::{FailoverPolicy, HostTracker},
+    limits::BodyLimits,
+    query::FeedFormat,
+    retry::RetryPolicy,
+    transport::TransportConfig,
+    RsshubApiClient, RsshubClientConfig, DEFAULT_HOST, DEFAULT_TIMEOUT,
+};
+
+/// Longest TTL or stale window accepted for cached responses.
+const MAX_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Builder for [`RsshubApiClient`], created with [`RsshubApiClient::builder`].
+///
+/// [`build`](Self::build) normalises hosts and rejects invalid settings with
+/// [`RsshubError::InvalidConfig`] naming the offending field.
+#[derive(Debug, Clone, Default)]
+pub struct RsshubApiClientBuilder {
+    hosts: Vec<String>,
+    feed_hosts: Vec<String>,
+    timeout: Option<Duration>,
+    retry: RetryPolicy,
+    cache: CacheConfig,
+    failover: FailoverPolicy,
+    auth: AuthConfig,
+    transport: TransportConfig,
+    body_limits: BodyLimits,
+    feed_format: Option<FeedFormat>,
+    naive_date_offset: Option<FixedOffset>,
+    http_client: Option<reqwest::Client>,
+    cache_backend: Option<Arc<dyn CacheBackend>>,
+    /// `RsshubClientConfig` shorthands that were applied, as (setting, shorthand) pairs,
+    /// so errors name the field the caller actually set
+    shorthands: Vec<(&'static str, &'static str)>,
+}
+
+impl RsshubApiClientBuilder {
+    /// Single upstream instance; replaces any hosts set before.
+    pub fn host(mut self, host: impl Into<String>) -> Self {
+        self.hosts = vec![host.into()];
+        self
+    }
+
+    /// Ordered upstream instances, primary first.
+    pub fn hosts<I, S>(mut self, hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.hosts = hosts.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Ordered instances for feed requests; defaults to the metadata hosts.
+    pub fn feed_hosts<I, S>(mut self, hosts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.feed_hosts = hosts.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Timeout for each whole request; defaults to 120 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self.shorthands
+            .retain(|(setting, _)| !setting.starts_with("retry_policy."));
+        self
+    }
+
+    pub fn cache_config(mut self, cache: CacheConfig) -> Self {
+        self.cache = cache;
+        self.shorthands
+            .retain(|(setting, _)| !setting.starts_with("cache."));
+        self
+    }
+
+    pub fn failover(mut self, failover: FailoverPolicy) -> Self {
+        self.failover = failover;
+        self
+    }
+
+    pub fn auth(mut self, auth: AuthConfig) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    pub fn transport(mut self, transport: TransportConfig) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Maximum response body size per endpoint.
+    pub fn body_limits(mut self, body_limits: BodyLimits) -> Self {
+        self.body_limits = body_limits;
+        self
+    }
+
+    /// Format requested for feeds whose path has no `format` parameter, e.g.
+    /// [`FeedFormat::Json`] for JSON Feed; by default the route's own format is used.
+    pub fn feed_format(mut self, format: FeedFormat) -> Self {
+        self.feed_format = Some(format);
+        self
+    }
+
+    /// UTC offset of feed dates written without a time zone, e.g. `+08:00` for Chinese
+    /// sites; by default they are taken as UTC.
+    pub fn naive_date_offset(mut self, offset: FixedOffset) -> Self {
+        self.naive_date_offset = Some(offset);
+        self
+    }
+
+    /// Use this HTTP client as is; `timeout` and `transport` are then not applied.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Store responses in `cache`, e.g. one shared with other clients or a Redis instance
+    /// shared between processes, instead of a [`MemoryCache`] sized by the cache config.
+    pub fn cache_backend(mut self, cache: Arc<dyn CacheBackend>) -> Self {
+        self.cache_backend = Some(cache);
+        self
+    }
+
+    /// Validate the settings and build the client.
+    pub fn build(self) -> Result<RsshubApiClient> {
+        let hosts = if self.hosts.is_empty() {
+            vec![DEFAULT_HOST.to_string()]
+        } else {
+            normalize_hosts("hosts", &self.hosts)?
+        };
+        let feed_hosts = if self.feed_hosts.is_empty() {
+            hosts.clone()
+        } else {
+            normalize_hosts("feed_hosts", &self.feed_hosts)?
+        };
+        let timeout = self.timeout.unwrap_or(Duration::from_secs(DEFAULT_TIMEOUT));
+        check(!timeout.is_zero(), "timeout", "must be greater than zero")?;
+        validate_retry(&self.retry).map_err(|e| self.blame_shorthand(e))?;
+        validate_cache(&self.cache).map_err(|e| self.blame_shorthand(e))?;
+        validate_failover(&self.failover)?;
+        validate_body_limits(&self.body_limits)?;
+
+        let client = match self.http_client {
+            Some(client) => client,
+            None => self.transport.build_client(timeout)?,
+        };
+        let cache = self
+            .cache_backend
+            .unwrap_or_else(|| Arc::new(MemoryCache::from_config(&self.cache)));
+        Ok(RsshubApiClient {
+            client,
+            host: hosts[0].clone(),
+            hosts,
+            feed_hosts,
+            health: Arc::new(HostTracker::new(self.failover)),
+            auth: self.auth,
+            cache,
+            cache_activity: Arc::default(),
+            parsed: Arc::default(),
+            cache_config: self.cache,
+            retry: self.retry,
+            body_limits: self.body_limits,
+            feed_format: self.feed_format,
+            naive_date_offset: self.naive_date_offset.unwrap_or(Utc.fix()),
+            in_flight: Arc::default(),
+        })
+    }
+}
+
+impl From<RsshubClientConfig> for RsshubApiClientBuilder {
+    fn from(config: RsshubClientConfig) -> Self {
+        let mut shorthands = Vec::new();
+        let mut retry = config.retry_policy.unwrap_or_default();
+        if let Some(retries) = config.retries {
+            retry.max_attempts = retries;
+            shorthands.push(("retry_policy.max_attempts", "retries"));
+        }
+        if let Some(backoff_ms) = config.retry_backoff_ms {
+            retry.initial_backoff = Duration::from_millis(backoff_ms);
+            shorthands.push(("retry_policy.initial_backoff", "retry_backoff_ms"));
+        }
+        let mut cache = config.cache.unwrap_or_default();
+        if let Some(ttl) = config.namespaces_ttl_secs {
+            cache.namespaces_ttl = Duration::from_secs(ttl);
+            shorthands.push(("cache.namespaces_ttl", "namespaces_ttl_secs"));
+        }
+        if let Some(ttl) = config.radar_rules_ttl_secs {
+            cache.radar_rules_ttl = Duration::from_secs(ttl);
+            shorthands.push(("cache.radar_rules_ttl", "radar_rules_ttl_secs"));
+        }
+        let hosts = match (config.hosts, config.host) {
+            (Some(hosts), _) if !hosts.is_empty() => hosts,
+            (_, Some(host)) => vec![host],
+            _ => Vec::new(),
+        };
+        Self {
+            hosts,
+            feed_hosts: config.feed_hosts.unwrap_or_default(),
+            timeout: config.timeout.map(Duration::from_secs),
+            retry,
+            cache,
+            failover: config.failover.unwrap_or_default(),
+            auth: config.auth.unwrap_or_default(),
+            transport: config.transport.unwrap_or_default(),
+            body_limits: config.body_limits.unwrap_or_default(),
+            feed_format: config.feed_format,
+            naive_date_offset: config.naive_date_offset,
+            http_client: None,
+            cache_backend: None,
+            shorthands,
+        }
+    }
+}
+
+impl RsshubApiClientBuilder {
+    /// Rename the field of a configuration error to the shorthand that set it, if any.
+    fn blame_shorthand(&self, err: RsshubError) -> RsshubError {
+        match err {
+            RsshubError::InvalidConfig { field, message } => {
+                let field = self
+                    .shorthands
+                    .iter()
+                    .find(|(setting, _)| *setting == field)
+                    .map_or(field, |(_, shorthand)| shorthand.to_string());
+                RsshubError::InvalidConfig { field, message }
+            }
+            err => err,
+        }
+    }
+}
+
+fn check(ok: bool, field: &str, message: &str) -> Result<()> {
+    if ok {
+        Ok(())
+    } else {
+        Err(RsshubError::invalid_config(field, message))
+    }
+}
+
+/// Parse each host as an `http(s)` base URL and drop trailing slashes.
+fn normalize_hosts(field: &str, hosts: &[String]) -> Result<Vec<String>> {
+    hosts
+        .iter()
+        .map(|host| {
+            let host = host.trim().trim_end_matches('/');
+            let url = url::Url::parse(host).map_err(|e| {
+                RsshubError::invalid_config(field, format!("{}: {e}", redact_url(host)))
+            })?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(RsshubError::invalid_config(
+                    field,
+                    format!("{}: scheme must be http or https", redact_url(host)),
+                ));
+            }
+            if url.query().is_some() || url.fragment().is_some() {
+                return Err(RsshubError::invalid_config(
+                    field,
+                    format!("{}: must not contain a query or fragment", redact_url(host)),
+                ));
+            }
+            Ok(host.to_string())
+        })
+        .collect()
+}
+
+fn validate_retry(retry: &RetryPolicy) -> Result<()> {
+    check(
+        retry.max_attempts >= 1,
+        "retry_policy.max_attempts",
+        "must be at least 1",
+    )?;
+    check(
+        retry.multiplier.is_finite() && retry.multiplier >= 1.0,
+        "retry_policy.multiplier",
+        "must be at least 1.0",
+    )?;
+    check(
+        (0.0..=1.0).contains(&retry.jitter),
+        "retry_policy.jitter",
+        "must be between 0.0 and 1.0",
+    )?;
+    check(
+        retry.initial_backoff <= retry.max_backoff,
+        "retry_policy.initial_backoff",
+        "must not exceed max_backoff",
+    )?;
+    check(
+        retry.deadline.is_none_or(|d| !d.is_zero()),
+        "retry_policy.deadline",
+        "must be greater than zero",
+    )
+}
+
+fn validate_cache(cache: &CacheConfig) -> Result<()> {
+    check(
+        cache.max_entries > 0,
+        "cache.max_entries",
+        "must be at least 1",
+    )?;
+    check(cache.max_bytes > 0, "cache.max_bytes", "must be at least 1")?;
+    check(
+        cache.disk_max_entries > 0,
+        "cache.disk_max_entries",
+        "must be at least 1",
+    )?;
+    check(
+        cache.disk_max_bytes > 0,
+        "cache.disk_max_bytes",
+        "must be at least 1",
+    )?;
+    let ttls = [
+        ("cache.namespaces_ttl", cache.namespaces_ttl),
+        ("cache.namespace_ttl", cache.namespace_ttl),
+        ("cache.radar_rules_ttl", cache.radar_rules_ttl),
+        ("cache.radar_rule_ttl", cache.radar_rule_ttl),
+        ("cache.category_ttl", cache.category_ttl),
+        ("cache.feed_ttl", cache.feed_ttl),
+    ];
+    for (field, ttl) in ttls {
+        check(ttl <= MAX_TTL, field, "must not exceed 30 days")?;
+    }
+    for (route, ttl) in &cache.feed_route_ttls {
+        check(
+            !route.trim_matches('/').is_empty(),
+            "cache.feed_route_ttls",
+            "route prefix must not be empty",
+        )?;
+        check(
+            *ttl <= MAX_TTL,
+            "cache.feed_route_ttls",
+            "must not exceed 30 days",
+        )?;
+    }
+    check(
+        cache.max_stale.is_none_or(|d| d <= MAX_TTL),
+        "cache.max_stale",
+        "must not exceed 30 days",
+    )
+}
+
+fn validate_failover(failover: &FailoverPolicy) -> Result<()> {
+    check(
+        failover.failure_threshold >= 1,
+        "failover.failure_threshold",
+        "must be at least 1",
+    )?;
+    check(
+        failover.latency_alpha > 0.0 && failover.latency_alpha <= 1.0,
+        "failover.latency_alpha",
+        "must be greater than 0.0 and at most 1.0",
+    )?;
+    check(
+        failover.slow_factor >= 1.0,
+        "failover.slow_factor",
+        "must be at least 1.0",
+    )
+}
+
+fn validate_body_limits(limits: &BodyLimits) -> Result<()> {
+    let limits = [
+        ("body_limits.namespaces", limits.namespaces),
+        ("body_limits.namespace", limits.namespace),
+        ("body_limits.radar_rules", limits.radar_rules),
+        ("body_limits.radar_rule", limits.radar_rule),
+        ("body_limits.category", limits.category),
+        ("body_limits.feed", limits.feed),
+    ];
+    for (field, limit) in limits {
+        check(limit > 0, field, "must be at least 1")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field_of(err: RsshubError) -> String {
+        match err {
+            RsshubError::InvalidConfig { field, .. } => field,
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn test_hosts_are_normalised() {
+        let client = RsshubApiClient::builder()
+            .hosts([
+                "https://rsshub.example/",
+                " https://mirror.example/rsshub// ",
+            ])
+            .build()
+            .unwrap();
+        assert_eq!(client.host, "https://rsshub.example");
+        assert_eq!(client.hosts[1], "https://mirror.example/rsshub");
+        assert_eq!(client.feed_hosts, client.hosts);
+    }
+
+    #[test]
+    fn test_invalid_fields_are_reported() {
+        let cases = [
+            (RsshubApiClient::builder().host("rsshub.example"), "hosts"),
+            (
+                RsshubApiClient::builder().feed_hosts(["ftp://rsshub.example"]),
+                "feed_hosts",
+            ),
+            (
+                RsshubApiClient::builder().host("https://rsshub.example/?key=x"),
+                "hosts",
+            ),
+            (
+                RsshubApiClient::builder().timeout(Duration::ZERO),
+                "timeout",
+            ),
+            (
+                RsshubApiClient::builder().cache_config(CacheConfig {
+                    feed_ttl: Duration::from_secs(60 * 24 * 60 * 60),
+                    ..Default::default()
+                }),
+                "cache.feed_ttl",
+            ),
+            (
+                RsshubApiClient::builder().failover(FailoverPolicy {
+                    latency_alpha: 0.0,
+                    ..Default::default()
+                }),
+                "failover.latency_alpha",
+            ),
+            (
+                RsshubApiClient::builder().failover(FailoverPolicy {
+                    slow_factor: 0.5,
+                    ..Default::default()
+                }),
+                "failover.slow_factor",
+            ),
+            (
+                RsshubApiClient::builder().body_limits(BodyLimits {
+                    feed: 0,
+                    ..Default::default()
+                }),
+                "body_limits.feed",
+            ),
+        ];
+        for (builder, field) in cases {
+            assert_eq!(field_of(builder.build().unwrap_err()), field);
+        }
+    }
+
+    #[test]
+    fn test_zero_retries_are_rejected_from_config() {
+        let err = RsshubApiClient::new(RsshubClientConfig {
+            retries: Some(0),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert_eq!(field_of(err), "retries");
+
+        let err = RsshubApiClient::new(RsshubClientConfig {
+            retry_policy: Some(RetryPolicy {
+                max_attempts: 0,
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert_eq!(field_of(err), "retry_policy.max_attempts");
+    }
+
+    #[test]
+    fn test_cache_backend_is_shared() {
+        let cache: Arc<dyn CacheBackend> = Arc::new(MemoryCache::default());
+        let a = RsshubApiClient::builder()
+            .cache_backend(cache.clone())
+            .build()
+            .unwrap();
+        let b = RsshubApiClient::builder()
+            .http_client(reqwest::Client::new())
+            .cache_backend(cache.clone())
+            .build()
+            .unwrap();
+        assert!(Arc::ptr_eq(&a.cache, &b.cache));
+        assert!(Arc::ptr_eq(&a.cache, &cache));
+    }
+}
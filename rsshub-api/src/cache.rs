@@ -0,0 +1,691 @@
+//! Response cache: the pluggable storage backend, the in-process LRU backend, and the
+//! HTTP validators kept with each entry.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use arc_swap::ArcSwapOption;
+use async_trait::async_trait;
+use lru::LruCache;
+use reqwest::header::{
+    HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{disk::FileCache, Fetched};
+
+/// HTTP validators remembered for a cached response.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let get = |name| {
+            headers
+                .get(name)
+                .and_then(|v: &HeaderValue| v.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            etag: get(ETAG),
+            last_modified: get(LAST_MODIFIED),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// Headers turning a GET into a conditional GET.
+    pub(crate) fn conditional_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(v) = self
+            .etag
+            .as_deref()
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(IF_NONE_MATCH, v);
+        }
+        if let Some(v) = self
+            .last_modified
+            .as_deref()
+            .and_then(|v| HeaderValue::from_str(v).ok())
+        {
+            headers.insert(IF_MODIFIED_SINCE, v);
+        }
+        headers
+    }
+}
+
+/// Savings from conditional requests answered with `304 Not Modified`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConditionalStats {
+    /// Conditional requests sent upstream
+    pub revalidations: u64,
+    /// Conditional requests answered with `304 Not Modified`
+    pub not_modified: u64,
+    /// Body bytes that did not have to be downloaded
+    pub bytes_saved: u64,
+    /// Parse time that did not have to be spent
+    pub parse_time_saved: Duration,
+}
+
+/// Sizing and per-endpoint TTLs of the response cache.
+///
+/// `max_entries`, `max_bytes` and the `disk_*` settings size the default [`MemoryCache`];
+/// the TTLs apply whatever the backend.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheConfig {
+    /// Maximum number of cached responses
+    pub max_entries: usize,
+    /// Maximum total size of cached responses, measured by their body size
+    pub max_bytes: usize,
+    /// TTL of `/api/namespace`
+    pub namespaces_ttl: Duration,
+    /// TTL of `/api/namespace/:namespace`
+    pub namespace_ttl: Duration,
+    /// TTL of `/api/radar/rules`
+    pub radar_rules_ttl: Duration,
+    /// TTL of `/api/radar/rules/:domain`
+    pub radar_rule_ttl: Duration,
+    /// TTL of `/api/category/:category`
+    pub category_ttl: Duration,
+    /// TTL of feeds without a matching entry in `feed_route_ttls`
+    pub feed_ttl: Duration,
+    /// Feed TTL overrides keyed by route prefix, e.g. `github/issue`; the longest match wins
+    pub feed_route_ttls: HashMap<String, Duration>,
+    /// Directory where entries are persisted across restarts; `None` keeps the cache in memory.
+    /// Ignored when the client is given its own cache backend
+    pub disk_dir: Option<PathBuf>,
+    /// Maximum number of files kept in `disk_dir`; the oldest are deleted first
+    pub disk_max_entries: usize,
+    /// Maximum total size of the files kept in `disk_dir`
+    pub disk_max_bytes: u64,
+    /// How long past its TTL an entry may still be served, marked stale, while it is
+    /// refreshed in the background or while upstream keeps failing; `None` disables this
+    pub max_stale: Option<Duration>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 512,
+            max_bytes: 64 * 1024 * 1024,
+            namespaces_ttl: Duration::from_secs(300),
+            namespace_ttl: Duration::from_secs(300),
+            radar_rules_ttl: Duration::from_secs(600),
+            radar_rule_ttl: Duration::from_secs(600),
+            category_ttl: Duration::from_secs(600),
+            feed_ttl: Duration::from_secs(60),
+            feed_route_ttls: HashMap::new(),
+            disk_dir: None,
+            disk_max_entries: 4096,
+            disk_max_bytes: 256 * 1024 * 1024,
+            max_stale: None,
+        }
+    }
+}
+
+impl CacheConfig {
+    /// TTL for the feed at `path`, honouring per-route overrides.
+    pub fn feed_ttl_for(&self, path: &str) -> Duration {
+        let path = path.trim_start_matches('/');
+        let path = path.split_once('?').map_or(path, |(p, _)| p);
+        self.feed_route_ttls
+            .iter()
+            .map(|(prefix, ttl)| (prefix.trim_matches('/'), ttl))
+            .filter(|(prefix, _)| {
+                path.strip_prefix(prefix)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.feed_ttl, |(_, ttl)| *ttl)
+    }
+}
+
+/// Hit, miss and eviction counters of the response cache.
+///
+/// Hits and misses are counted by the client, which knows the TTLs; evictions, entries
+/// and bytes come from the backend.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheStats {
+    /// Lookups answered by a fresh entry
+    pub hits: u64,
+    /// Lookups answered by an expired entry within the `max_stale` window
+    pub stale_hits: u64,
+    /// Lookups that had to go upstream
+    pub misses: u64,
+    /// Entries dropped to stay within `max_entries` / `max_bytes`
+    pub evictions: u64,
+    /// Entries currently cached
+    pub entries: usize,
+    /// Total body size of the entries currently cached
+    pub bytes: usize,
+}
+
+/// A cached response, stored as the JSON form of the parsed value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheEntry {
+    pub value: serde_json::Value,
+    /// When upstream last sent or confirmed this value
+    pub fetched_at: SystemTime,
+    pub validators: Validators,
+    /// Size of the response body the value was parsed from
+    pub body_len: usize,
+    /// Time it took to parse that body
+    pub parse_time: Duration,
+    /// Upstream instance that sent or last confirmed this value
+    pub host: Option<String>,
+}
+
+impl CacheEntry {
+    pub fn age(&self) -> Duration {
+        self.fetched_at.elapsed().unwrap_or_default()
+    }
+}
+
+/// Serialized form of a [`CacheEntry`], shared by the file and Redis backends.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct StoredEntry {
+    pub url: String,
+    pub fetched_at_ms: u64,
+    #[serde(flatten)]
+    pub validators: Validators,
+    pub body_len: usize,
+    pub parse_time_us: u64,
+    #[serde(default)]
+    pub host: Option<String>,
+    pub value: serde_json::Value,
+}
+
+impl StoredEntry {
+    pub fn new(key: &str, entry: &CacheEntry) -> Self {
+        Self {
+            url: key.to_string(),
+            fetched_at_ms: entry
+                .fetched_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            validators: entry.validators.clone(),
+            body_len: entry.body_len,
+            parse_time_us: entry.parse_time.as_micros() as u64,
+            host: entry.host.clone(),
+            value: entry.value.clone(),
+        }
+    }
+
+    /// The entry, if it was stored under `key` rather than under a colliding name.
+    pub fn into_entry(self, key: &str) -> Option<CacheEntry> {
+        (self.url == key).then(|| CacheEntry {
+            value: self.value,
+            fetched_at: UNIX_EPOCH + Duration::from_millis(self.fetched_at_ms),
+            validators: self.validators,
+            body_len: self.body_len,
+            parse_time: Duration::from_micros(self.parse_time_us),
+            host: self.host,
+        })
+    }
+}
+
+/// Storage of cached responses, keyed by request URL.
+///
+/// Backends only store entries; freshness is decided by the client from each entry's
+/// `fetched_at`. They are best-effort: a backend that fails to read or write logs the
+/// problem and behaves as if the entry was missing. Share one backend between clients,
+/// or between processes with a shared store, to share a warm cache.
+#[async_trait]
+pub trait CacheBackend: Send + Sync + fmt::Debug {
+    async fn get(&self, key: &str) -> Option<CacheEntry>;
+
+    /// Store `entry`, replacing any previous entry for `key`.
+    async fn put(&self, key: &str, entry: CacheEntry);
+
+    async fn invalidate(&self, key: &str);
+
+    /// Eviction, entry and byte counts; backends that cannot tell leave them at zero.
+    fn stats(&self) -> CacheStats;
+}
+
+/// Outcome of a cache lookup.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Lookup {
+    Fresh {
+        value: serde_json::Value,
+        age: Duration,
+        host: Option<String>,
+    },
+    /// Expired, but within the `max_stale` window
+    Stale {
+        value: serde_json::Value,
+        age: Duration,
+        host: Option<String>,
+    },
+    Miss,
+}
+
+impl Lookup {
+    /// Classify `entry`: younger than `ttl` is fresh, younger than `ttl + max_stale` is
+    /// stale.
+    pub fn classify(entry: Option<CacheEntry>, ttl: Duration, max_stale: Option<Duration>) -> Self {
+        let Some(entry) = entry else {
+            return Self::Miss;
+        };
+        let age = entry.age();
+        let stale_limit = max_stale.map(|m| ttl.saturating_add(m));
+        if age <= ttl {
+            Self::Fresh {
+                value: entry.value,
+                age,
+                host: entry.host,
+            }
+        } else if stale_limit.is_some_and(|limit| age <= limit) {
+            Self::Stale {
+                value: entry.value,
+                age,
+                host: entry.host,
+            }
+        } else {
+            Self::Miss
+        }
+    }
+}
+
+/// Per-client cache bookkeeping that does not belong in the backend.
+///
+/// Lookup counters are atomics, as they are bumped on every hit.
+#[derive(Debug, Default)]
+pub(crate) struct CacheActivity {
+    hits: AtomicU64,
+    stale_hits: AtomicU64,
+    misses: AtomicU64,
+    conditional: Mutex<ConditionalStats>,
+    /// Keys with a background refresh in flight
+    refreshing: Mutex<HashSet<String>>,
+}
+
+impl CacheActivity {
+    pub fn record_lookup(&self, lookup: &Lookup) {
+        let counter = match lookup {
+            Lookup::Fresh { .. } => &self.hits,
+            Lookup::Stale { .. } => &self.stale_hits,
+            Lookup::Miss => &self.misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_stale_hit(&self) {
+        self.stale_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn refreshing(&self) -> std::sync::MutexGuard<'_, HashSet<String>> {
+        self.refreshing
+            .lock()
+            .expect("Failed to lock refreshing mutex")
+    }
+
+    fn conditional(&self) -> std::sync::MutexGuard<'_, ConditionalStats> {
+        self.conditional
+            .lock()
+            .expect("Failed to lock conditional stats mutex")
+    }
+
+    /// Claim the background refresh of `key`; `false` if one is already running.
+    pub fn begin_refresh(&self, key: &str) -> bool {
+        self.refreshing().insert(key.to_string())
+    }
+
+    pub fn end_refresh(&self, key: &str) {
+        self.refreshing().remove(key);
+    }
+
+    pub fn record_revalidation(&self) {
+        self.conditional().revalidations += 1;
+    }
+
+    /// Count the download and parse of `entry` saved by a `304 Not Modified`.
+    pub fn record_not_modified(&self, entry: &CacheEntry) {
+        let mut conditional = self.conditional();
+        conditional.not_modified += 1;
+        conditional.bytes_saved += entry.body_len as u64;
+        conditional.parse_time_saved += entry.parse_time;
+    }
+
+    /// Client-side counters combined with the backend's `stats`.
+    pub fn stats(&self, backend: CacheStats) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            stale_hits: self.stale_hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            ..backend
+        }
+    }
+
+    pub fn conditional_stats(&self) -> ConditionalStats {
+        self.conditional().clone()
+    }
+}
+
+/// Parsed value of one cache key, shared as an `Arc` and read without locking.
+///
+/// Serves the value while it is fresh or within the `max_stale` window, so the largest
+/// responses skip the backend and JSON decoding on every hit; older values fall through
+/// to the backend and revalidation.
+#[derive(Debug)]
+pub(crate) struct ParsedSlot<T>(ArcSwapOption<ParsedEntry<T>>);
+
+#[derive(Debug)]
+struct ParsedEntry<T> {
+    value: Arc<T>,
+    fetched_at: SystemTime,
+    host: Option<String>,
+}
+
+impl<T> Default for ParsedSlot<T> {
+    fn default() -> Self {
+        Self(ArcSwapOption::empty())
+    }
+}
+
+impl<T> ParsedSlot<T> {
+    /// The value, if younger than `ttl`, or marked stale if younger than `ttl + max_stale`.
+    pub fn get(&self, ttl: Duration, max_stale: Option<Duration>) -> Option<Fetched<Arc<T>>> {
+        let guard = self.0.load();
+        let entry = guard.as_ref()?;
+        let age = entry.fetched_at.elapsed().unwrap_or_default();
+        let stale = age > ttl;
+        let limit = ttl.saturating_add(max_stale.unwrap_or_default());
+        (age <= limit).then(|| Fetched {
+            value: entry.value.clone(),
+            age,
+            stale,
+            host: entry.host.clone(),
+        })
+    }
+
+    pub fn store(&self, fetched: &Fetched<Arc<T>>) {
+        self.0.store(Some(Arc::new(ParsedEntry {
+            value: fetched.value.clone(),
+            fetched_at: SystemTime::now() - fetched.age,
+            host: fetched.host.clone(),
+        })));
+    }
+}
+
+/// In-process LRU backend bounded by entry count and total body size, optionally
+/// backed by a [`FileCache`] so a restarted process starts warm.
+///
+/// Disk entries are loaded lazily on first access, so startup does not read the whole
+/// cache directory.
+#[derive(Debug)]
+pub struct MemoryCache {
+    inner: Mutex<MemoryInner>,
+    disk: Option<FileCache>,
+}
+
+#[derive(Debug)]
+struct MemoryInner {
+    entries: LruCache<String, CacheEntry>,
+    max_entries: usize,
+    max_bytes: usize,
+    bytes: usize,
+    evictions: u64,
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::from_config(&CacheConfig::default())
+    }
+}
+
+impl MemoryCache {
+    pub fn new(max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            inner: Mutex::new(MemoryInner {
+                entries: LruCache::unbounded(),
+                max_entries,
+                max_bytes,
+                bytes: 0,
+                evictions: 0,
+            }),
+            disk: None,
+        }
+    }
+
+    /// Sized by `max_entries` and `max_bytes`, with `disk_dir` as disk tier when set.
+    pub fn from_config(config: &CacheConfig) -> Self {
+        let cache = Self::new(config.max_entries, config.max_bytes);
+        match &config.disk_dir {
+            Some(dir) => cache.with_disk(FileCache::with_limits(
+                dir,
+                config.disk_max_entries,
+                config.disk_max_bytes,
+            )),
+            None => cache,
+        }
+    }
+
+    /// Write entries through to `disk` and load missing ones from it.
+    pub fn with_disk(mut self, disk: FileCache) -> Self {
+        self.disk = Some(disk);
+        self
+    }
+
+    fn inner(&self) -> std::sync::MutexGuard<'_, MemoryInner> {
+        self.inner.lock().expect("Failed to lock cache mutex")
+    }
+}
+
+impl MemoryInner {
+    /// Insert `entry`, evicting least recently used entries to stay within bounds.
+    ///
+    /// Entries larger than the whole byte budget are not kept in memory.
+    fn insert(&mut self, key: &str, entry: CacheEntry) {
+        self.remove(key);
+        if self.max_entries == 0 || entry.body_len > self.max_bytes {
+            return;
+        }
+        while self.entries.len() >= self.max_entries || self.bytes + entry.body_len > self.max_bytes
+        {
+            let Some((_, evicted)) = self.entries.pop_lru() else {
+                break;
+            };
+            self.bytes -= evicted.body_len;
+            self.evictions += 1;
+        }
+        self.bytes += entry.body_len;
+        self.entries.put(key.to_string(), entry);
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(old) = self.entries.pop(key) {
+            self.bytes -= old.body_len;
+        }
+    }
+}
+
+#[async_trait]
+impl CacheBackend for MemoryCache {
+    /// Disk entries are read without holding the lock, so a slow disk does not block
+    /// lookups of other keys.
+    async fn get(&self, key: &str) -> Option<CacheEntry> {
+        let cached = self.inner().entries.get(key).cloned();
+        if cached.is_some() {
+            return cached;
+        }
+        let entry = self.disk.as_ref()?.load(key).await?;
+        let mut inner = self.inner();
+        // A `put` while the disk was read holds the newer value
+        if let Some(newer) = inner.entries.get(key) {
+            return Some(newer.clone());
+        }
+        inner.insert(key, entry.clone());
+        Some(entry)
+    }
+
+    async fn put(&self, key: &str, entry: CacheEntry) {
+        if let Some(disk) = &self.disk {
+            disk.store(key, &entry).await;
+        }
+        self.inner().insert(key, entry);
+    }
+
+    async fn invalidate(&self, key: &str) {
+        if let Some(disk) = &self.disk {
+            disk.remove(key).await;
+        }
+        self.inner().remove(key);
+    }
+
+    fn stats(&self) -> CacheStats {
+        let inner = self.inner();
+        CacheStats {
+            evictions: inner.evictions,
+            entries: inner.entries.len(),
+            bytes: inner.bytes,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(body_len: usize) -> CacheEntry {
+        CacheEntry {
+            value: serde_json::Value::Null,
+            fetched_at: SystemTime::now(),
+            validators: Validators::default(),
+            body_len,
+            parse_time: Duration::ZERO,
+            host: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lru_eviction_by_entry_count() {
+        let cache = MemoryCache::new(2, usize::MAX);
+        cache.put("a", entry(1)).await;
+        cache.put("b", entry(1)).await;
+        // Touch "a" so that "b" becomes the least recently used entry
+        assert!(cache.get("a").await.is_some());
+        cache.put("c", entry(1)).await;
+        assert!(cache.get("b").await.is_none());
+        assert!(cache.get("a").await.is_some());
+        let stats = cache.stats();
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.entries, 2);
+    }
+
+    #[tokio::test]
+    async fn test_lru_eviction_by_byte_budget() {
+        let cache = MemoryCache::new(10, 100);
+        cache.put("a", entry(40)).await;
+        cache.put("b", entry(40)).await;
+        cache.put("c", entry(40)).await;
+        assert_eq!(cache.stats().bytes, 80);
+        assert!(cache.get("a").await.is_none());
+        // Too large to ever fit
+        cache.put("huge", entry(101)).await;
+        assert!(cache.get("huge").await.is_none());
+        assert_eq!(cache.stats().entries, 2);
+
+        cache.invalidate("b").await;
+        assert!(cache.get("b").await.is_none());
+        assert_eq!(cache.stats().bytes, 40);
+    }
+
+    #[test]
+    fn test_stale_window() {
+        let old = || {
+            Some(CacheEntry {
+                fetched_at: SystemTime::now() - Duration::from_secs(90),
+                ..entry(1)
+            })
+        };
+        let ttl = Duration::from_secs(60);
+        assert_eq!(Lookup::classify(old(), ttl, None), Lookup::Miss);
+        assert!(matches!(
+            Lookup::classify(old(), ttl, Some(Duration::from_secs(60))),
+            Lookup::Stale { age, .. } if age >= Duration::from_secs(90)
+        ));
+        assert_eq!(
+            Lookup::classify(old(), ttl, Some(Duration::from_secs(10))),
+            Lookup::Miss
+        );
+        assert!(matches!(
+            Lookup::classify(old(), Duration::MAX, None),
+            Lookup::Fresh { .. }
+        ));
+
+        let activity = CacheActivity::default();
+        assert!(activity.begin_refresh("a"));
+        assert!(!activity.begin_refresh("a"));
+        activity.end_refresh("a");
+        assert!(activity.begin_refresh("a"));
+    }
+
+    #[test]
+    fn test_parsed_slot_serves_fresh_and_stale_values() {
+        let slot = ParsedSlot::default();
+        assert!(slot.get(Duration::MAX, None).is_none());
+        slot.store(&Fetched {
+            value: Arc::new(vec![1, 2, 3]),
+            age: Duration::from_secs(30),
+            stale: false,
+            host: None,
+        });
+        let fetched = slot.get(Duration::from_secs(60), None).unwrap();
+        assert!(fetched.age >= Duration::from_secs(30));
+        assert!(!fetched.stale);
+        // Handed out without copying
+        assert!(Arc::ptr_eq(
+            &fetched.value,
+            &slot.get(Duration::MAX, None).unwrap().value
+        ));
+        assert!(slot.get(Duration::from_secs(10), None).is_none());
+        let stale = slot
+            .get(Duration::from_secs(10), Some(Duration::from_secs(60)))
+            .unwrap();
+        assert!(stale.stale);
+        assert!(slot
+            .get(Duration::from_secs(10), Some(Duration::from_secs(5)))
+            .is_none());
+    }
+
+    #[test]
+    fn test_feed_ttl_prefers_longest_route_prefix() {
+        let config = CacheConfig {
+            feed_route_ttls: HashMap::from([
+                ("github".to_string(), Duration::from_secs(10)),
+                ("/github/issue/".to_string(), Duration::from_secs(20)),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.feed_ttl_for("/github/issue/DIYgod/RSSHub?limit=5"),
+            Duration::from_secs(20)
+        );
+        assert_eq!(
+            config.feed_ttl_for("github/trending"),
+            Duration::from_secs(10)
+        );
+        assert_eq!(config.feed_ttl_for("githubx/foo"), config.feed_ttl);
+    }
+}
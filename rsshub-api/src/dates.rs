@@ -0,0 +1,310 @@
+//! Normalization of the free-form dates feeds carry into UTC timestamps.
+
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Offset, Timelike, Utc};
+
+use crate::{FeedChannel, FeedItem, FeedResponse};
+
+/// Date and time layouts tried after separators and Chinese date words are normalized
+const NAIVE_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%d %H:%M"];
+
+/// RFC 2822 variants with a full month name, a missing second or no time
+const LENIENT_RFC2822_FORMATS: [&str; 4] = [
+    "%d %B %Y %H:%M:%S",
+    "%d %B %Y %H:%M",
+    "%B %d %Y %H:%M:%S",
+    "%B %d, %Y %H:%M:%S",
+];
+
+/// Parse a date as found in feeds into UTC; dates without a time zone are taken as UTC.
+///
+/// Accepts RFC 3339 and RFC 2822, including common deviations such as a wrong weekday,
+/// full month names or `UTC` / `GMT+8` zones, Unix timestamps in seconds or milliseconds,
+/// and numeric dates like `2025-08-12 09:30`, `2025/8/12` or `2025年8月12日 09:30`.
+pub fn parse_timestamp(date: &str) -> Option<DateTime<Utc>> {
+    parse_date(date, Utc.fix())
+}
+
+/// [`parse_timestamp`] with dates without a time zone taken in `naive_offset`.
+pub(crate) fn parse_date(date: &str, naive_offset: FixedOffset) -> Option<DateTime<Utc>> {
+    let date = date.trim();
+    if date.is_empty() {
+        return None;
+    }
+    DateTime::parse_from_rfc3339(date)
+        .or_else(|_| DateTime::parse_from_rfc2822(date))
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+        .or_else(|| unix_timestamp(date))
+        .or_else(|| lenient_rfc2822(date, naive_offset))
+        .or_else(|| numeric_date(date, naive_offset))
+}
+
+fn unix_timestamp(date: &str) -> Option<DateTime<Utc>> {
+    if !date.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let value: i64 = date.parse().ok()?;
+    match date.len() {
+        10 => DateTime::from_timestamp(value, 0),
+        13 => DateTime::from_timestamp_millis(value),
+        _ => None,
+    }
+}
+
+/// RFC 2822 as actually written: weekdays are ignored, months may be spelt out and the zone
+/// may be a name, an offset without minutes or missing.
+fn lenient_rfc2822(date: &str, naive_offset: FixedOffset) -> Option<DateTime<Utc>> {
+    let date = match date.split_once(", ") {
+        Some((weekday, rest)) if weekday.chars().all(|c| c.is_ascii_alphabetic()) => rest,
+        _ => date,
+    };
+    let (date, offset) = split_zone(date).unwrap_or((date, naive_offset));
+    LENIENT_RFC2822_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+        .and_then(|naive| in_offset(naive, offset))
+}
+
+/// Year-first numeric dates with `-`, `/` or `.` separators or Chinese date words.
+fn numeric_date(date: &str, naive_offset: FixedOffset) -> Option<DateTime<Utc>> {
+    let (date, offset) = split_zone(date).unwrap_or((date, naive_offset));
+    let mut normalized = String::with_capacity(date.len());
+    for c in date.chars() {
+        match c {
+            '年' | '月' | '/' => normalized.push('-'),
+            '日' | 'T' => normalized.push(' '),
+            '时' | '時' | '点' | '點' | '分' => normalized.push(':'),
+            '秒' => {}
+            c => normalized.push(c),
+        }
+    }
+    let normalized = normalized.split_whitespace().collect::<Vec<_>>().join(" ");
+    let normalized = normalized.trim_end_matches(':');
+    // Dots separate the date in `2025.08.12`, but not the seconds from their fraction
+    let normalized = match normalized.split_once(' ') {
+        Some((day, time)) => format!("{} {time}", day.replace('.', "-")),
+        None => normalized.replace('.', "-"),
+    };
+    if let Some(zoned) = ["%Y-%m-%d %H:%M:%S%.f%z", "%Y-%m-%d %H:%M%z"]
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(&normalized, format).ok())
+    {
+        return Some(zoned.with_timezone(&Utc));
+    }
+    let naive = NAIVE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&normalized, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(&normalized, "%Y-%m-%d")
+                .ok()
+                .and_then(|day| day.and_hms_opt(0, 0, 0))
+        })?;
+    in_offset(naive, offset)
+}
+
+/// Split a trailing zone such as `GMT`, `UTC+8`, `+0800` or `+08:00` off `date`.
+fn split_zone(date: &str) -> Option<(&str, FixedOffset)> {
+    let (rest, zone) = date.rsplit_once(' ')?;
+    let upper = zone.to_ascii_uppercase();
+    let offset = match upper.as_str() {
+        "Z" | "UT" | "UTC" | "GMT" => "+00:00",
+        _ => upper
+            .strip_prefix("UTC")
+            .or_else(|| upper.strip_prefix("GMT"))
+            .unwrap_or(&upper),
+    };
+    let offset = match offset.as_bytes() {
+        // Hours only, as in `GMT+8`
+        [b'+' | b'-', hours @ ..] if (1..=2).contains(&hours.len()) => {
+            let hours: i32 = offset[1..].parse().ok()?;
+            let secs = hours * 3600;
+            if offset.starts_with('-') {
+                FixedOffset::west_opt(secs)?
+            } else {
+                FixedOffset::east_opt(secs)?
+            }
+        }
+        [b'+' | b'-', ..] => offset.parse().ok()?,
+        _ => return None,
+    };
+    Some((rest.trim_end(), offset))
+}
+
+fn in_offset(naive: NaiveDateTime, offset: FixedOffset) -> Option<DateTime<Utc>> {
+    naive
+        .and_local_timezone(offset)
+        .single()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+/// Fill in the normalized timestamps of every item, flagging items whose dates could not be
+/// parsed and reporting them in `warnings`.
+pub(crate) fn normalize(feed: &mut FeedResponse, naive_offset: FixedOffset) {
+    let mut unparsed = Vec::new();
+    for item in &mut feed.items {
+        let mut parse = |date: &Option<String>| {
+            let date = date.as_deref()?;
+            let parsed = parse_date(date, naive_offset);
+            if parsed.is_none() {
+                unparsed.push(date.to_string());
+            }
+            parsed
+        };
+        item.published_at = parse(&item.pub_date);
+        item.updated_at = parse(&item.updated);
+        item.invalid_date = (item.pub_date.is_some() && item.published_at.is_none())
+            || (item.updated.is_some() && item.updated_at.is_none());
+    }
+    let channel = &mut feed.channel;
+    channel.last_build_at = channel
+        .last_build_date
+        .as_deref()
+        .and_then(|date| parse_date(date, naive_offset));
+    if let Some(example) = unparsed.first() {
+        feed.warnings.push(format!(
+            "{} dates could not be parsed, e.g. `{example}`",
+            unparsed.len()
+        ));
+    }
+}
+
+impl FeedItem {
+    /// Normalized publication time, falling back to the last update
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.published_at.or(self.updated_at)
+    }
+}
+
+impl FeedChannel {
+    /// How long the feed may be cached, from its TTL
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl_minutes
+            .map(|minutes| Duration::from_secs(u64::from(minutes) * 60))
+    }
+
+    /// Whether `time` falls into the hours or days the feed asks not to be fetched in.
+    ///
+    /// RSS gives skip hours in GMT, so `time` is compared in UTC.
+    pub fn is_skipped_at(&self, time: DateTime<Utc>) -> bool {
+        let hour = u8::try_from(time.hour()).unwrap_or_default();
+        let day = time.format("%A").to_string();
+        self.skip_hours.contains(&hour)
+            || self.skip_days.iter().any(|d| d.eq_ignore_ascii_case(&day))
+    }
+}
+
+impl FeedResponse {
+    /// Keep the items whose [`FeedItem::timestamp`] is within `since..=until`; items
+    /// without one are dropped when either bound is set.
+    pub fn retain_between(&mut self, since: Option<DateTime<Utc>>, until: Option<DateTime<Utc>>) {
+        if since.is_none() && until.is_none() {
+            return;
+        }
+        self.items.retain(|item| {
+            item.timestamp().is_some_and(|t| {
+                since.is_none_or(|since| t >= since) && until.is_none_or(|until| t <= until)
+            })
+        });
+    }
+
+    /// Order items by [`FeedItem::timestamp`], keeping undated items last in their original
+    /// order.
+    pub fn sort_by_date(&mut self, newest_first: bool) {
+        self.items
+            .sort_by(|a, b| match (a.timestamp(), b.timestamp()) {
+                (Some(a), Some(b)) if newest_first => b.cmp(&a),
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(date: &str) -> Option<DateTime<Utc>> {
+        Some(date.parse().unwrap())
+    }
+
+    #[test]
+    fn test_standard_and_lenient_dates() {
+        let cases = [
+            ("2025-08-12T09:30:00.000Z", "2025-08-12T09:30:00Z"),
+            ("2025-08-12T17:30:00+08:00", "2025-08-12T09:30:00Z"),
+            ("Tue, 12 Aug 2025 09:30:00 GMT", "2025-08-12T09:30:00Z"),
+            ("12 Aug 2025 17:30:00 +0800", "2025-08-12T09:30:00Z"),
+            // Wrong weekday, zone names and offsets RFC 2822 does not allow
+            ("Wed, 12 Aug 2025 09:30:00 GMT", "2025-08-12T09:30:00Z"),
+            ("Tue, 12 Aug 2025 09:30:00 UTC", "2025-08-12T09:30:00Z"),
+            ("Tue, 12 August 2025 17:30:00 GMT+8", "2025-08-12T09:30:00Z"),
+            ("1754991000", "2025-08-12T09:30:00Z"),
+            ("1754991000000", "2025-08-12T09:30:00Z"),
+        ];
+        for (date, expected) in cases {
+            assert_eq!(parse_timestamp(date), utc(expected), "{date}");
+        }
+    }
+
+    #[test]
+    fn test_numeric_and_chinese_dates() {
+        let cst = FixedOffset::east_opt(8 * 3600).unwrap();
+        let cases = [
+            ("2025-08-12 17:30:00", "2025-08-12T09:30:00Z"),
+            ("2025-08-12 17:30", "2025-08-12T09:30:00Z"),
+            ("2025/8/12 17:30:00", "2025-08-12T09:30:00Z"),
+            ("2025.08.12 17:30:00.250", "2025-08-12T09:30:00.250Z"),
+            ("2025-08-12", "2025-08-11T16:00:00Z"),
+            ("2025年8月12日", "2025-08-11T16:00:00Z"),
+            ("2025年08月12日 17:30", "2025-08-12T09:30:00Z"),
+            ("2025年8月12日17时30分", "2025-08-12T09:30:00Z"),
+            ("2025年8月12日 17点30分15秒", "2025-08-12T09:30:15Z"),
+            // An explicit zone wins over the default offset
+            ("2025-08-12 09:30:00 UTC", "2025-08-12T09:30:00Z"),
+            ("2025-08-12 17:30:00+08:00", "2025-08-12T09:30:00Z"),
+        ];
+        for (date, expected) in cases {
+            assert_eq!(parse_date(date, cst), utc(expected), "{date}");
+        }
+        for date in ["", "3小时前", "yesterday", "2025-13-45", "12345"] {
+            assert_eq!(parse_date(date, cst), None, "{date}");
+        }
+    }
+
+    #[test]
+    fn test_items_are_filtered_and_sorted_by_date() {
+        let item = |title: &str, pub_date: Option<&str>| FeedItem {
+            title: title.to_string(),
+            pub_date: pub_date.map(str::to_string),
+            ..FeedItem::default()
+        };
+        let mut feed = FeedResponse {
+            items: vec![
+                item("old", Some("Mon, 11 Aug 2025 09:00:00 GMT")),
+                item("undated", None),
+                item("new", Some("2025-08-13T09:00:00Z")),
+                item("broken", Some("刚刚")),
+                item("mid", Some("2025-08-12 09:00")),
+            ],
+            ..FeedResponse::default()
+        };
+        normalize(&mut feed, Utc.fix());
+        assert_eq!(feed.warnings, ["1 dates could not be parsed, e.g. `刚刚`"]);
+        assert!(feed.items[3].invalid_date);
+        assert!(!feed.items[1].invalid_date);
+
+        feed.sort_by_date(true);
+        let titles: Vec<&str> = feed.items.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, ["new", "mid", "old", "undated", "broken"]);
+
+        feed.retain_between(utc("2025-08-12T00:00:00Z"), None);
+        let titles: Vec<&str> = feed.items.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, ["new", "mid"]);
+        feed.retain_between(None, utc("2025-08-12T09:00:00Z"));
+        assert_eq!(feed.items.len(), 1);
+    }
+}
@@ -0,0 +1,398 @@
+//! Filesystem backend of the response cache, so a restarted process starts warm.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::cache::{CacheBackend, CacheEntry, CacheStats, StoredEntry};
+
+/// Filesystem backend: a directory holding one JSON file per cached URL.
+///
+/// Bounded by entry count and total file size; when a write goes over either limit, the
+/// files written longest ago, i.e. with the oldest fetch time, are deleted. Usually used
+/// as the disk tier of a [`MemoryCache`](crate::MemoryCache) via `CacheConfig::disk_dir`.
+/// Files are read and written on tokio's blocking thread pool.
+///
+/// The files are tracked in an index built by one directory scan on first access, so
+/// writes and [`stats`](CacheBackend::stats) do not list the directory.
+#[derive(Debug, Clone)]
+pub struct FileCache {
+    dir: PathBuf,
+    max_entries: usize,
+    max_bytes: u64,
+    index: Arc<Mutex<Option<DiskIndex>>>,
+}
+
+/// Size and write time of every entry file, oldest first.
+#[derive(Debug, Default)]
+struct DiskIndex {
+    files: HashMap<PathBuf, (SystemTime, u64)>,
+    by_age: BTreeSet<(SystemTime, PathBuf)>,
+    bytes: u64,
+    evictions: u64,
+}
+
+impl DiskIndex {
+    /// Index the entry files in `dir`.
+    ///
+    /// Files are rewritten whenever upstream sends or confirms their value, so the
+    /// modification time tracks the entry's fetch time without parsing every file.
+    fn scan(dir: &Path) -> Self {
+        let mut index = Self::default();
+        let files = match fs::read_dir(dir) {
+            Ok(files) => files,
+            Err(e) => {
+                if e.kind() != io::ErrorKind::NotFound {
+                    warn!("Failed to list cache directory {}: {e}", dir.display());
+                }
+                return index;
+            }
+        };
+        for file in files.flatten() {
+            let path = file.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            if let Ok(meta) = file.metadata() {
+                let written = meta.modified().unwrap_or(UNIX_EPOCH);
+                index.insert(path, written, meta.len());
+            }
+        }
+        index
+    }
+
+    fn insert(&mut self, path: PathBuf, written: SystemTime, len: u64) {
+        self.remove(&path);
+        self.by_age.insert((written, path.clone()));
+        self.files.insert(path, (written, len));
+        self.bytes += len;
+    }
+
+    fn remove(&mut self, path: &Path) {
+        if let Some((written, len)) = self.files.remove(path) {
+            self.by_age.remove(&(written, path.to_path_buf()));
+            self.bytes -= len;
+        }
+    }
+
+    /// Drop the oldest files from the index until it fits within the limits, returning
+    /// their paths.
+    fn evict(&mut self, max_entries: usize, max_bytes: u64) -> Vec<PathBuf> {
+        let mut evicted = Vec::new();
+        while self.files.len() > max_entries || self.bytes > max_bytes {
+            let Some((_, path)) = self.by_age.first().cloned() else {
+                break;
+            };
+            self.remove(&path);
+            self.evictions += 1;
+            evicted.push(path);
+        }
+        evicted
+    }
+}
+
+impl FileCache {
+    /// Bounded by the `disk_max_entries` / `disk_max_bytes` defaults of
+    /// [`CacheConfig`](crate::CacheConfig).
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let defaults = crate::CacheConfig::default();
+        Self::with_limits(dir, defaults.disk_max_entries, defaults.disk_max_bytes)
+    }
+
+    pub fn with_limits(dir: impl Into<PathBuf>, max_entries: usize, max_bytes: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            max_entries,
+            max_bytes,
+            index: Arc::default(),
+        }
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        let digest = Sha256::digest(key.as_bytes());
+        self.dir.join(format!("{}.json", hex::encode(digest)))
+    }
+
+    fn lock_index(&self) -> MutexGuard<'_, Option<DiskIndex>> {
+        self.index.lock().expect("Failed to lock disk index mutex")
+    }
+
+    /// The index, scanning the directory if this is the first access. Blocking, but the
+    /// scan runs without holding the lock.
+    fn index(&self) -> MutexGuard<'_, Option<DiskIndex>> {
+        {
+            let index = self.lock_index();
+            if index.is_some() {
+                return index;
+            }
+        }
+        let scanned = DiskIndex::scan(&self.dir);
+        let mut index = self.lock_index();
+        if index.is_none() {
+            *index = Some(scanned);
+        }
+        index
+    }
+
+    /// Read the entry for `key`; missing, unreadable or corrupted files yield `None`.
+    pub(crate) async fn load(&self, key: &str) -> Option<CacheEntry> {
+        let cache = self.clone();
+        let key = key.to_string();
+        blocking(move || cache.read_entry(&key)).await.flatten()
+    }
+
+    /// Persist `entry` for `key`, replacing any previous file atomically, then evict
+    /// the oldest files if the directory is over its limits.
+    pub(crate) async fn store(&self, key: &str, entry: &CacheEntry) {
+        let cache = self.clone();
+        let path = self.path_for(key);
+        let stored = StoredEntry::new(key, entry);
+        blocking(move || cache.write_entry(path, &stored)).await;
+    }
+
+    pub(crate) async fn remove(&self, key: &str) {
+        let cache = self.clone();
+        let path = self.path_for(key);
+        blocking(move || {
+            remove_file(&path);
+            if let Some(index) = cache.index().as_mut() {
+                index.remove(&path);
+            }
+        })
+        .await;
+    }
+
+    fn read_entry(&self, key: &str) -> Option<CacheEntry> {
+        // Seed the index before the first file is touched
+        drop(self.index());
+        let path = self.path_for(key);
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                warn!("Failed to read cache file {}: {e}", path.display());
+                return None;
+            }
+        };
+        match serde_json::from_slice::<StoredEntry>(&bytes) {
+            Ok(entry) => entry.into_entry(key),
+            Err(e) => {
+                warn!("Ignoring corrupted cache file {}: {e}", path.display());
+                remove_file(&path);
+                if let Some(index) = self.index().as_mut() {
+                    index.remove(&path);
+                }
+                None
+            }
+        }
+    }
+
+    fn write_entry(&self, path: PathBuf, entry: &StoredEntry) {
+        let len = match write_atomic(&self.dir, &path, entry) {
+            Ok(len) => len,
+            Err(e) => {
+                warn!("Failed to write cache file {}: {e}", path.display());
+                return;
+            }
+        };
+        let evicted = match self.index().as_mut() {
+            Some(index) => {
+                index.insert(path, SystemTime::now(), len);
+                index.evict(self.max_entries, self.max_bytes)
+            }
+            None => return,
+        };
+        for path in evicted {
+            remove_file(&path);
+        }
+    }
+}
+
+#[async_trait]
+impl CacheBackend for FileCache {
+    async fn get(&self, key: &str) -> Option<CacheEntry> {
+        self.load(key).await
+    }
+
+    async fn put(&self, key: &str, entry: CacheEntry) {
+        self.store(key, &entry).await;
+    }
+
+    async fn invalidate(&self, key: &str) {
+        self.remove(key).await;
+    }
+
+    /// Entry files and their size on disk, from the index; zero until the cache is first
+    /// read or written.
+    fn stats(&self) -> CacheStats {
+        let index = self.lock_index();
+        let Some(index) = index.as_ref() else {
+            return CacheStats::default();
+        };
+        CacheStats {
+            evictions: index.evictions,
+            entries: index.files.len(),
+            bytes: index.bytes as usize,
+            ..Default::default()
+        }
+    }
+}
+
+/// Run filesystem work off the async runtime; `None` if the task panicked.
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> Option<T> {
+    match tokio::task::spawn_blocking(f).await {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!("Cache file task failed: {e}");
+            None
+        }
+    }
+}
+
+fn remove_file(path: &Path) {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            warn!("Failed to remove cache file {}: {e}", path.display());
+        }
+        _ => {}
+    }
+}
+
+/// Write to a temporary file in the same directory, then rename it over `path`,
+/// so readers never observe a partially written entry. Returns the file size.
+///
+/// The file is not fsynced: the cache is best-effort, and an entry torn by a crash is
+/// discarded as corrupted when it is next read.
+fn write_atomic(dir: &Path, path: &Path, entry: &StoredEntry) -> io::Result<u64> {
+    let bytes = serde_json::to_vec(entry)?;
+    fs::create_dir_all(dir)?;
+    let tmp = dir.join(format!(
+        ".{}.{:016x}.tmp",
+        std::process::id(),
+        fastrand::u64(..)
+    ));
+    let result = fs::write(&tmp, &bytes).and_then(|()| fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result.map(|()| bytes.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Validators;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "rsshub-api-{name}-{}-{:x}",
+            std::process::id(),
+            fastrand::u64(..)
+        ));
+        fs::create_dir_all(&dir).expect("Failed to create temp dir");
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_keeps_timestamp_and_validators() {
+        let dir = temp_dir("round-trip");
+        let disk = FileCache::new(&dir);
+        let fetched_at = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        disk.store(
+            "https://rsshub.example/api/namespace",
+            &CacheEntry {
+                value: serde_json::json!({"bilibili": {"routes": null}}),
+                fetched_at,
+                validators: Validators {
+                    etag: Some("\"abc\"".to_string()),
+                    last_modified: None,
+                },
+                body_len: 42,
+                parse_time: Duration::from_micros(7),
+                host: Some("https://rsshub.example".to_string()),
+            },
+        )
+        .await;
+
+        let loaded = disk
+            .load("https://rsshub.example/api/namespace")
+            .await
+            .expect("entry should load");
+        assert_eq!(loaded.fetched_at, fetched_at);
+        assert_eq!(loaded.validators.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(loaded.body_len, 42);
+        assert_eq!(loaded.host.as_deref(), Some("https://rsshub.example"));
+        assert!(disk
+            .load("https://rsshub.example/api/radar/rules")
+            .await
+            .is_none());
+        assert_eq!(disk.stats().entries, 1);
+        disk.remove("https://rsshub.example/api/namespace").await;
+        assert!(disk
+            .load("https://rsshub.example/api/namespace")
+            .await
+            .is_none());
+        assert_eq!(disk.stats().entries, 0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_oldest_entries_are_evicted_over_the_limit() {
+        let dir = temp_dir("evict");
+        let disk = FileCache::with_limits(&dir, 2, u64::MAX);
+        let entry = CacheEntry {
+            value: serde_json::Value::Null,
+            fetched_at: SystemTime::now(),
+            validators: Validators::default(),
+            body_len: 0,
+            parse_time: Duration::ZERO,
+            host: None,
+        };
+        for key in ["a", "b", "c"] {
+            disk.store(key, &entry).await;
+            // Keep modification times apart on coarse-grained filesystems
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let stats = disk.stats();
+        assert_eq!((stats.entries, stats.evictions), (2, 1));
+        assert!(disk.load("a").await.is_none());
+        assert!(disk.load("b").await.is_some());
+        assert!(disk.load("c").await.is_some());
+
+        // A new instance counts the existing files once, on first access
+        let disk = FileCache::with_limits(&dir, 2, u64::MAX);
+        assert_eq!(disk.stats().entries, 0);
+        assert!(disk.load("b").await.is_some());
+        assert_eq!(disk.stats().entries, 2);
+        disk.store("e", &entry).await;
+        assert!(disk.load("b").await.is_none());
+        assert_eq!(disk.stats().entries, 2);
+
+        let disk = FileCache::with_limits(&dir, 10, 1);
+        disk.store("d", &entry).await;
+        assert_eq!(disk.stats().entries, 0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_entry_is_skipped_and_removed() {
+        let dir = temp_dir("corrupted");
+        let disk = FileCache::new(&dir);
+        let key = "https://rsshub.example/api/radar/rules";
+        let path = disk.path_for(key);
+        fs::write(&path, b"{\"url\": \"https://rsshub.ex").expect("Failed to write");
+
+        assert!(disk.load(key).await.is_none());
+        assert!(!path.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,140 @@
+//! Error types returned by the RSSHub API client.
+
+use std::sync::Arc;
+
+use reqwest::StatusCode;
+use thiserror::Error;
+
+/// Maximum number of characters of an error response body kept in [`RsshubError::Http`].
+const BODY_EXCERPT_LEN: usize = 512;
+
+pub type Result<T, E = RsshubError> = std::result::Result<T, E>;
+
+/// Cloneable so that one failed upstream fetch can be reported to every caller waiting on it.
+#[derive(Debug, Clone, Error)]
+pub enum RsshubError {
+    /// The request could not be sent or the connection failed mid-way
+    #[error("request to {url} failed: {source}")]
+    Transport {
+        url: String,
+        #[source]
+        source: Arc<reqwest::Error>,
+    },
+    /// The request did not complete within the configured timeout
+    #[error("request to {url} timed out")]
+    Timeout { url: String },
+    /// Upstream answered with a non-success status other than 404
+    #[error("{url} returned HTTP {status}: {body}")]
+    Http {
+        url: String,
+        status: StatusCode,
+        body: String,
+    },
+    /// Upstream answered with 404 (unknown namespace, rule, category or route)
+    #[error("{url} not found (HTTP 404)")]
+    NotFound { url: String },
+    /// The response body is larger than the limit configured for its endpoint
+    #[error("response from {url} exceeds the limit of {limit} bytes")]
+    ResponseTooLarge { url: String, limit: usize },
+    /// The response body did not match the expected JSON shape
+    #[error("failed to deserialize response from {url} at `{path}`: {source}")]
+    Deserialize {
+        url: String,
+        path: String,
+        #[source]
+        source: Arc<serde_json::Error>,
+    },
+    /// The feed body could not be parsed
+    #[error("failed to parse feed: {message}")]
+    FeedParse { message: String },
+    /// The client configuration is invalid; `field` names the offending setting
+    #[error("invalid client configuration `{field}`: {message}")]
+    InvalidConfig { field: String, message: String },
+}
+
+/// A tool or request argument that does not name one of its accepted values.
+///
+/// Returned by the `FromStr` impls of argument enums such as
+/// [`OpenccConversion`](crate::OpenccConversion); unlike [`RsshubError::InvalidConfig`]
+/// it blames the caller's input, not the client configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("unknown {argument} `{value}`, expected one of: {}", expected.join(", "))]
+pub struct ParseArgumentError {
+    pub argument: &'static str,
+    pub value: String,
+    pub expected: Vec<&'static str>,
+}
+
+impl RsshubError {
+    /// Map a `reqwest` error for `url` into `Timeout` or `Transport`.
+    ///
+    /// The URL inside the `reqwest` error is dropped, as it may carry an access key.
+    pub(crate) fn from_reqwest(url: &str, source: reqwest::Error) -> Self {
+        if source.is_timeout() {
+            Self::Timeout {
+                url: url.to_string(),
+            }
+        } else {
+            Self::Transport {
+                url: url.to_string(),
+                source: Arc::new(source.without_url()),
+            }
+        }
+    }
+
+    /// Build the error for an invalid client setting named `field`.
+    pub(crate) fn invalid_config(field: &str, message: impl Into<String>) -> Self {
+        Self::InvalidConfig {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Build the error for a non-success response, keeping an excerpt of the body.
+    pub(crate) fn from_status(url: &str, status: StatusCode, body: &str) -> Self {
+        if status == StatusCode::NOT_FOUND {
+            return Self::NotFound {
+                url: url.to_string(),
+            };
+        }
+        let mut excerpt: String = body.chars().take(BODY_EXCERPT_LEN).collect();
+        if excerpt.len() < body.len() {
+            excerpt.push('…');
+        }
+        Self::Http {
+            url: url.to_string(),
+            status,
+            body: excerpt,
+        }
+    }
+
+    /// HTTP status associated with this error, if upstream answered at all.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Http { status, .. } => Some(*status),
+            Self::NotFound { .. } => Some(StatusCode::NOT_FOUND),
+            _ => None,
+        }
+    }
+
+    /// URL of the request that failed, if known.
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            Self::Transport { url, .. }
+            | Self::Timeout { url }
+            | Self::Http { url, .. }
+            | Self::NotFound { url }
+            | Self::ResponseTooLarge { url, .. }
+            | Self::Deserialize { url, .. } => Some(url),
+            Self::FeedParse { .. } | Self::InvalidConfig { .. } => None,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::NotFound { .. })
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::Timeout { .. })
+    }
+}
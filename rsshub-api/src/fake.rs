@@ -0,0 +1,215 @@
+//! In-memory [`RsshubApi`] backend for tests (feature `fake`).
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+
+use crate::{
+    api::RsshubApi,
+    error::{Result, RsshubError},
+    parse_feed, CategoryInfo, CategoryItems, FeedQuery, FeedResponse, Fetched, NamespaceResp,
+    RoutesMap, RulesInfo, RulesResp,
+};
+
+/// Serves fixed data without network access.
+///
+/// Unknown namespaces, rules, categories and feeds fail with [`RsshubError::NotFound`],
+/// like a real instance answering 404. Values are always fresh and have no host. Feeds
+/// are looked up by route path; query parameters such as a [`FeedQuery`] are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct FakeRsshub {
+    namespaces: HashMap<String, CategoryInfo>,
+    radar_rules: RulesResp,
+    feeds: HashMap<String, FeedResponse>,
+}
+
+impl FakeRsshub {
+    /// An empty backend; every lookup is not found.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loaded from the fixtures in `rsshub-api/tests`: the namespaces of `category.json`,
+    /// the rules of `rules.json`, and `feed.xml` as the feed `ithome/it`.
+    pub fn from_fixtures() -> Self {
+        let namespaces: CategoryItems =
+            serde_json::from_str(include_str!("../tests/category.json"))
+                .expect("category.json fixture is valid");
+        let radar_rules: RulesResp = serde_json::from_str(include_str!("../tests/rules.json"))
+            .expect("rules.json fixture is valid");
+        let feed =
+            parse_feed(include_str!("../tests/feed.xml")).expect("feed.xml fixture is valid");
+        Self {
+            namespaces: namespaces.0,
+            radar_rules,
+            feeds: HashMap::from([("ithome/it".to_string(), feed)]),
+        }
+    }
+
+    /// Add or replace a namespace with its routes.
+    pub fn with_namespace(mut self, namespace: impl Into<String>, info: CategoryInfo) -> Self {
+        self.namespaces.insert(namespace.into(), info);
+        self
+    }
+
+    /// Add or replace the radar rules of a domain.
+    pub fn with_radar_rule(mut self, domain: impl Into<String>, rule: RulesInfo) -> Self {
+        self.radar_rules.insert(domain.into(), rule);
+        self
+    }
+
+    /// Add or replace the feed served for a route path.
+    pub fn with_feed(mut self, path: &str, feed: FeedResponse) -> Self {
+        self.feeds.insert(feed_key(path).to_string(), feed);
+        self
+    }
+}
+
+#[async_trait]
+impl RsshubApi for FakeRsshub {
+    async fn fetch_all_namespaces(&self) -> Result<Fetched<Arc<NamespaceResp>>> {
+        let namespaces = self
+            .namespaces
+            .iter()
+            .map(|(name, info)| (name.clone(), routes_map(info)))
+            .collect();
+        Ok(fresh(Arc::new(namespaces)))
+    }
+
+    async fn fetch_namespace(&self, namespace: &str) -> Result<Fetched<RoutesMap>> {
+        self.namespaces
+            .get(namespace)
+            .map(|info| fresh(routes_map(info)))
+            .ok_or_else(|| not_found(format!("/api/namespace/{namespace}")))
+    }
+
+    async fn fetch_all_radar_rules(&self) -> Result<Fetched<Arc<RulesResp>>> {
+        Ok(fresh(Arc::new(self.radar_rules.clone())))
+    }
+
+    async fn fetch_radar_rule(&self, domain: &str) -> Result<Fetched<RulesInfo>> {
+        self.radar_rules
+            .get(domain)
+            .map(|rule| fresh(rule.clone()))
+            .ok_or_else(|| not_found(format!("/api/radar/rules/{domain}")))
+    }
+
+    /// Namespaces with at least one route in `category`, keeping only those routes.
+    async fn fetch_category(&self, category: &str) -> Result<Fetched<CategoryItems>> {
+        let mut items = HashMap::new();
+        for (name, info) in &self.namespaces {
+            let routes: HashMap<_, _> = info
+                .routes
+                .iter()
+                .filter(|(_, route)| {
+                    route
+                        .categories
+                        .as_ref()
+                        .is_some_and(|c| c.iter().any(|c| c == category))
+                })
+                .map(|(key, route)| (key.clone(), route.clone()))
+                .collect();
+            if !routes.is_empty() {
+                let mut info = info.clone();
+                info.routes = routes;
+                items.insert(name.clone(), info);
+            }
+        }
+        if items.is_empty() {
+            return Err(not_found(format!("/api/category/{category}")));
+        }
+        Ok(fresh(CategoryItems(items)))
+    }
+
+    async fn fetch_feed(&self, path: &str) -> Result<Fetched<FeedResponse>> {
+        let path = feed_key(path);
+        self.feeds
+            .get(path)
+            .map(|feed| fresh(feed.clone()))
+            .ok_or_else(|| not_found(format!("/{path}")))
+    }
+}
+
+fn feed_key(path: &str) -> &str {
+    let path = path.split('?').next().unwrap_or_default();
+    path.strip_prefix('/').unwrap_or(path)
+}
+
+fn routes_map(info: &CategoryInfo) -> RoutesMap {
+    RoutesMap {
+        routes: Some(info.routes.clone()),
+    }
+}
+
+fn fresh<T>(value: T) -> Fetched<T> {
+    Fetched {
+        value,
+        age: Duration::ZERO,
+        stale: false,
+        host: None,
+    }
+}
+
+fn not_found(url: String) -> RsshubError {
+    RsshubError::NotFound { url }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fixtures_are_served() {
+        let fake = FakeRsshub::from_fixtures();
+
+        let namespaces = fake.fetch_all_namespaces().await.unwrap();
+        assert_eq!(namespaces.value.len(), 210);
+        assert!(!namespaces.stale);
+
+        let kr = fake.fetch_namespace("36kr").await.unwrap().value;
+        assert!(kr.routes.unwrap().contains_key("/hot-list/:category?"));
+
+        let feed = fake.fetch_feed("/ithome/it").await.unwrap().value;
+        assert_eq!(feed.title, "IT之家");
+        assert!(!feed.items.is_empty());
+        let query = FeedQuery::new().limit(1);
+        let limited = fake.fetch_feed_with("ithome/it", &query).await.unwrap();
+        assert_eq!(limited.value.items.len(), feed.items.len());
+
+        let rules = fake.fetch_all_radar_rules().await.unwrap().value;
+        let (domain, _) = rules.iter().next().unwrap();
+        assert!(fake.fetch_radar_rule(domain).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_category_keeps_matching_routes_only() {
+        let fake = FakeRsshub::from_fixtures();
+        let popular = fake.fetch_category("popular").await.unwrap().value;
+        assert!(popular.0.contains_key("36kr"));
+        assert!(popular.0.len() < 210);
+        for info in popular.0.values() {
+            assert!(info.routes.values().all(|route| route
+                .categories
+                .as_ref()
+                .is_some_and(|c| c.iter().any(|c| c == "popular"))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unknown_items_are_not_found() {
+        let fake = FakeRsshub::new();
+        let err = fake.fetch_namespace("nope").await.unwrap_err();
+        assert!(err.is_not_found());
+        assert_eq!(err.url(), Some("/api/namespace/nope"));
+        assert!(fake
+            .fetch_category("nope")
+            .await
+            .unwrap_err()
+            .is_not_found());
+        assert!(fake
+            .fetch_feed("nope/feed")
+            .await
+            .unwrap_err()
+            .is_not_found());
+    }
+}
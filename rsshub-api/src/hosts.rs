@@ -0,0 +1,246 @@
+//! Health tracking for multiple upstream RSSHub instances.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Controls when an upstream instance is considered unhealthy and skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailoverPolicy {
+    /// Consecutive failures after which a host is put in cooldown
+    pub failure_threshold: u32,
+    /// How long a host in cooldown is only tried after all other hosts
+    pub cooldown: Duration,
+    /// Weight of the newest sample in the latency moving average (0.0..=1.0)
+    pub latency_alpha: f64,
+    /// A host whose latency average exceeds the fastest available host's by more than
+    /// this factor is tried after the faster hosts
+    pub slow_factor: f64,
+}
+
+impl Default for FailoverPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            cooldown: Duration::from_secs(30),
+            latency_alpha: 0.3,
+            slow_factor: 3.0,
+        }
+    }
+}
+
+/// Point-in-time health of one upstream instance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostHealth {
+    pub host: String,
+    /// Failed requests since the last success
+    pub consecutive_failures: u32,
+    /// Moving average of the time it took to get an answer
+    pub latency_ewma: Option<Duration>,
+    /// Time left until the host is preferred again; `None` when it is available
+    pub cooldown_remaining: Option<Duration>,
+}
+
+#[derive(Debug, Default)]
+struct HostState {
+    consecutive_failures: u32,
+    latency_ewma: Option<Duration>,
+    cooldown_until: Option<Instant>,
+}
+
+impl HostState {
+    fn cooldown_remaining(&self, now: Instant) -> Option<Duration> {
+        self.cooldown_until
+            .and_then(|until| until.checked_duration_since(now))
+            .filter(|d| !d.is_zero())
+    }
+}
+
+/// Health of every host the client talks to, shared by metadata and feed requests.
+#[derive(Debug, Default)]
+pub(crate) struct HostTracker {
+    policy: FailoverPolicy,
+    states: Mutex<HashMap<String, HostState>>,
+}
+
+impl HostTracker {
+    pub fn new(policy: FailoverPolicy) -> Self {
+        Self {
+            policy,
+            states: Mutex::default(),
+        }
+    }
+
+    fn states(&self) -> std::sync::MutexGuard<'_, HashMap<String, HostState>> {
+        self.states
+            .lock()
+            .expect("Failed to lock host health mutex")
+    }
+
+    /// Order `hosts` for the next request.
+    ///
+    /// Available hosts keep their configured order, except that hosts slower than
+    /// `slow_factor` times the fastest available host move after the others; hosts
+    /// without latency samples yet are never demoted. Hosts in cooldown go last, the one
+    /// closest to the end of its cooldown first.
+    pub fn order<'a>(&self, hosts: &'a [String]) -> Vec<&'a str> {
+        let now = Instant::now();
+        let states = self.states();
+        let scored: Vec<(Option<Duration>, Option<Duration>, &str)> = hosts
+            .iter()
+            .map(|host| {
+                let state = states.get(host);
+                let remaining = state.and_then(|s| s.cooldown_remaining(now));
+                let latency = state.and_then(|s| s.latency_ewma);
+                (remaining, latency, host.as_str())
+            })
+            .collect();
+        let fastest = scored
+            .iter()
+            .filter(|(remaining, _, _)| remaining.is_none())
+            .filter_map(|(_, latency, _)| *latency)
+            .min();
+        let slow_limit = fastest.map(|f| f.mul_f64(self.policy.slow_factor.max(1.0)));
+        let mut ordered: Vec<(Option<Duration>, bool, &str)> = scored
+            .into_iter()
+            .map(|(remaining, latency, host)| {
+                let slow = latency.zip(slow_limit).is_some_and(|(l, limit)| l > limit);
+                (remaining, slow, host)
+            })
+            .collect();
+        // Stable sort: `None` (available) sorts first, fast before slow, and ties keep
+        // configured order
+        ordered.sort_by_key(|(remaining, slow, _)| (*remaining, *slow));
+        ordered.into_iter().map(|(_, _, host)| host).collect()
+    }
+
+    /// Record an answer from `host` that took `latency`.
+    pub fn record_success(&self, host: &str, latency: Duration) {
+        let alpha = self.policy.latency_alpha.clamp(0.0, 1.0);
+        let mut states = self.states();
+        let state = states.entry(host.to_string()).or_default();
+        state.consecutive_failures = 0;
+        state.cooldown_until = None;
+        state.latency_ewma = Some(match state.latency_ewma {
+            Some(avg) => avg.mul_f64(1.0 - alpha) + latency.mul_f64(alpha),
+            None => latency,
+        });
+    }
+
+    /// Record a failed request to `host`, starting its cooldown once the threshold is hit.
+    pub fn record_failure(&self, host: &str) {
+        let mut states = self.states();
+        let state = states.entry(host.to_string()).or_default();
+        state.consecutive_failures += 1;
+        if state.consecutive_failures >= self.policy.failure_threshold.max(1) {
+            state.cooldown_until = Some(Instant::now() + self.policy.cooldown);
+        }
+    }
+
+    /// Health of each of `hosts`, in the given order.
+    pub fn health(&self, hosts: &[String]) -> Vec<HostHealth> {
+        let now = Instant::now();
+        let states = self.states();
+        hosts
+            .iter()
+            .map(|host| {
+                let state = states.get(host);
+                HostHealth {
+                    host: host.clone(),
+                    consecutive_failures: state.map_or(0, |s| s.consecutive_failures),
+                    latency_ewma: state.and_then(|s| s.latency_ewma),
+                    cooldown_remaining: state.and_then(|s| s.cooldown_remaining(now)),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hosts() -> Vec<String> {
+        vec![
+            "https://primary.example".to_string(),
+            "https://secondary.example".to_string(),
+            "https://public.example".to_string(),
+        ]
+    }
+
+    #[test]
+    fn test_failing_host_is_moved_last_after_threshold() {
+        let tracker = HostTracker::new(FailoverPolicy {
+            failure_threshold: 2,
+            ..Default::default()
+        });
+        let hosts = hosts();
+
+        tracker.record_failure("https://primary.example");
+        assert_eq!(tracker.order(&hosts)[0], "https://primary.example");
+        tracker.record_failure("https://primary.example");
+        assert_eq!(
+            tracker.order(&hosts),
+            vec![
+                "https://secondary.example",
+                "https://public.example",
+                "https://primary.example",
+            ]
+        );
+
+        tracker.record_success("https://primary.example", Duration::from_millis(10));
+        assert_eq!(tracker.order(&hosts)[0], "https://primary.example");
+    }
+
+    #[test]
+    fn test_cooldown_expires() {
+        let tracker = HostTracker::new(FailoverPolicy {
+            failure_threshold: 1,
+            cooldown: Duration::ZERO,
+            ..Default::default()
+        });
+        let hosts = hosts();
+        tracker.record_failure("https://primary.example");
+        assert_eq!(tracker.order(&hosts)[0], "https://primary.example");
+        assert_eq!(tracker.health(&hosts)[0].consecutive_failures, 1);
+        assert_eq!(tracker.health(&hosts)[0].cooldown_remaining, None);
+    }
+
+    #[test]
+    fn test_slow_host_is_tried_after_faster_ones() {
+        let tracker = HostTracker::new(FailoverPolicy {
+            latency_alpha: 1.0,
+            ..Default::default()
+        });
+        let hosts = hosts();
+        tracker.record_success("https://primary.example", Duration::from_millis(900));
+        tracker.record_success("https://secondary.example", Duration::from_millis(100));
+        assert_eq!(
+            tracker.order(&hosts),
+            vec![
+                "https://secondary.example",
+                "https://public.example",
+                "https://primary.example",
+            ]
+        );
+
+        // Within the factor, configured order wins
+        tracker.record_success("https://primary.example", Duration::from_millis(250));
+        assert_eq!(tracker.order(&hosts)[0], "https://primary.example");
+    }
+
+    #[test]
+    fn test_latency_is_averaged() {
+        let tracker = HostTracker::new(FailoverPolicy {
+            latency_alpha: 0.5,
+            ..Default::default()
+        });
+        tracker.record_success("https://primary.example", Duration::from_millis(100));
+        tracker.record_success("https://primary.example", Duration::from_millis(300));
+        let health = tracker.health(&hosts());
+        assert_eq!(health[0].latency_ewma, Some(Duration::from_millis(200)));
+        assert_eq!(health[1].latency_ewma, None);
+    }
+}
@@ -0,0 +1,815 @@
+//! Rendering of HTML item descriptions as Markdown or plain text.
+//!
+//! This is a lenient converter for the HTML fragments feeds carry, not a full HTML parser:
+//! unknown tags are dropped but their text kept, and unclosed elements end with the input.
+
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use serde::Deserialize;
+
+use crate::{error::ParseArgumentError, FeedResponse};
+
+/// Elements whose content is dropped along with them
+const SKIPPED: [&str; 9] = [
+    "script", "style", "noscript", "template", "head", "svg", "object", "canvas", "iframe",
+];
+
+/// Elements that start and end a paragraph-like block
+const BLOCKS: [&str; 20] = [
+    "p",
+    "div",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "main",
+    "aside",
+    "nav",
+    "figure",
+    "figcaption",
+    "address",
+    "details",
+    "summary",
+    "dl",
+    "dt",
+    "dd",
+    "form",
+    "fieldset",
+    "center",
+];
+
+/// How item descriptions are delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentFormat {
+    /// As published by the feed
+    #[default]
+    Html,
+    Markdown,
+    /// Plain text, with link targets in parentheses
+    Text,
+}
+
+impl ContentFormat {
+    pub const ALL: [Self; 3] = [Self::Html, Self::Markdown, Self::Text];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Markdown => "markdown",
+            Self::Text => "text",
+        }
+    }
+
+    /// `html` in this format
+    pub fn render(self, html: &str) -> String {
+        match self {
+            Self::Html => html.to_string(),
+            Self::Markdown => html_to_markdown(html),
+            Self::Text => html_to_text(html),
+        }
+    }
+}
+
+impl fmt::Display for ContentFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ContentFormat {
+    type Err = ParseArgumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|f| f.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseArgumentError {
+                argument: "content format",
+                value: s.to_string(),
+                expected: Self::ALL.map(Self::as_str).to_vec(),
+            })
+    }
+}
+
+impl FeedResponse {
+    /// Render every item description in `format`; the raw content is left as is.
+    pub fn convert_descriptions(&mut self, format: ContentFormat) {
+        if format == ContentFormat::Html {
+            return;
+        }
+        for item in &mut self.items {
+            item.description = format.render(&item.description);
+        }
+    }
+}
+
+/// Markdown for an HTML fragment, keeping links, images with their alt text, emphasis,
+/// headings, lists, quotes, code and tables, and dropping scripts, styles and tracking
+/// pixels.
+pub fn html_to_markdown(html: &str) -> String {
+    Renderer::new(true).run(html)
+}
+
+/// Plain text for an HTML fragment: paragraphs and list items on their own lines, link
+/// targets in parentheses and images as their alt text.
+pub fn html_to_text(html: &str) -> String {
+    Renderer::new(false).run(html)
+}
+
+struct Tag {
+    name: String,
+    end: bool,
+    self_closing: bool,
+    attrs: Vec<(String, String)>,
+}
+
+impl Tag {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.trim())
+            .filter(|v| !v.is_empty())
+    }
+}
+
+struct Renderer {
+    markdown: bool,
+    out: String,
+    /// Whitespace was seen since the last output
+    pending_space: bool,
+    /// Nothing was output since an opening marker, so whitespace is dropped
+    suppress_space: bool,
+    /// A list marker was just written
+    fresh_item: bool,
+    /// Output offsets and targets of the open links
+    links: Vec<(usize, Option<String>)>,
+    /// Output offsets of the open quotes
+    quotes: Vec<usize>,
+    /// Next number of each open list, `None` for bullets
+    lists: Vec<Option<usize>>,
+    pre: usize,
+    table_rows: usize,
+    row_cells: usize,
+}
+
+impl Renderer {
+    fn new(markdown: bool) -> Self {
+        Self {
+            markdown,
+            out: String::new(),
+            pending_space: false,
+            suppress_space: false,
+            fresh_item: false,
+            links: Vec::new(),
+            quotes: Vec::new(),
+            lists: Vec::new(),
+            pre: 0,
+            table_rows: 0,
+            row_cells: 0,
+        }
+    }
+
+    fn run(mut self, html: &str) -> String {
+        let mut rest = html;
+        while let Some(lt) = rest.find('<') {
+            self.text(&rest[..lt]);
+            rest = &rest[lt..];
+            if let Some(after) = rest.strip_prefix("<!--") {
+                rest = after.find("-->").map_or("", |end| &after[end + 3..]);
+            } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+                let end = after.find("]]>").unwrap_or(after.len());
+                self.text(&after[..end]);
+                rest = after.get(end + 3..).unwrap_or_default();
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            } else if let Some((tag, len)) = parse_tag(rest) {
+                rest = &rest[len..];
+                if !tag.end && !tag.self_closing && SKIPPED.contains(&tag.name.as_str()) {
+                    rest = skip_element(rest, &tag.name);
+                }
+                self.tag(&tag);
+            } else {
+                self.text("<");
+                rest = &rest[1..];
+            }
+        }
+        self.text(rest);
+        self.finish()
+    }
+
+    fn text(&mut self, text: &str) {
+        for c in decode_entities(text).chars() {
+            if self.pre > 0 {
+                if c != '\r' {
+                    self.out.push(c);
+                }
+            } else if c.is_whitespace() {
+                self.pending_space = true;
+            } else {
+                self.content();
+                self.out.push(c);
+            }
+        }
+    }
+
+    /// Prepare for visible output: indent list continuations or separate it from the
+    /// previous word.
+    fn content(&mut self) {
+        if self.out.ends_with('\n') {
+            if !self.lists.is_empty() {
+                self.out.push_str(&"  ".repeat(self.lists.len()));
+            }
+        } else if self.pending_space
+            && !self.suppress_space
+            && !self.out.is_empty()
+            && !self.out.ends_with(' ')
+        {
+            self.out.push(' ');
+        }
+        self.pending_space = false;
+        self.suppress_space = false;
+        self.fresh_item = false;
+    }
+
+    fn open_marker(&mut self, marker: &str) {
+        self.content();
+        self.out.push_str(marker);
+        self.suppress_space = true;
+    }
+
+    fn close_marker(&mut self, marker: &str) {
+        if self.suppress_space && self.out.ends_with(marker) {
+            // Nothing between the markers
+            self.out.truncate(self.out.len() - marker.len());
+            self.suppress_space = false;
+        } else {
+            self.out.push_str(marker);
+        }
+    }
+
+    fn end_line(&mut self) {
+        if self.fresh_item {
+            return;
+        }
+        let trimmed = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed);
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+        self.pending_space = false;
+    }
+
+    /// Separate blocks by a blank line, or by a line break inside lists
+    fn block(&mut self) {
+        self.end_line();
+        if self.lists.is_empty() && !self.out.is_empty() && !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+    }
+
+    fn tag(&mut self, tag: &Tag) {
+        let name = tag.name.as_str();
+        match (name, tag.end) {
+            _ if BLOCKS.contains(&name) => self.block(),
+            ("br", _) => {
+                if self.pre > 0 {
+                    self.out.push('\n');
+                } else if !self.out.ends_with("\n\n") {
+                    self.end_line();
+                    if !self.out.is_empty() && !self.out.ends_with('\n') {
+                        self.out.push('\n');
+                    }
+                }
+            }
+            ("hr", false) => {
+                self.block();
+                self.out.push_str("---");
+                self.block();
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", end) => {
+                self.block();
+                if !end && self.markdown {
+                    let level = usize::from(name.as_bytes()[1] - b'0');
+                    self.open_marker(&format!("{} ", "#".repeat(level)));
+                }
+            }
+            ("strong" | "b", end) => self.emphasis(end, "**"),
+            ("em" | "i", end) => self.emphasis(end, "_"),
+            ("del" | "s" | "strike", end) => self.emphasis(end, "~~"),
+            ("code", end) if self.pre == 0 => self.emphasis(end, "`"),
+            ("pre", false) => {
+                self.block();
+                if self.markdown {
+                    self.out.push_str("```\n");
+                }
+                self.pre += 1;
+            }
+            ("pre", true) => {
+                self.pre = self.pre.saturating_sub(1);
+                self.close_pre();
+            }
+            ("a", false) => {
+                self.content();
+                self.suppress_space = true;
+                let href = tag.attr("href").map(str::to_string);
+                self.links.push((self.out.len(), href));
+            }
+            ("a", true) => {
+                if let Some((start, href)) = self.links.pop() {
+                    self.close_link(start, href);
+                }
+            }
+            ("img", false) => self.image(tag),
+            ("iframe" | "video" | "audio" | "embed", false) => self.embed(tag),
+            ("blockquote", false) => {
+                self.block();
+                self.quotes.push(self.out.len());
+            }
+            ("blockquote", true) => {
+                if let Some(start) = self.quotes.pop() {
+                    self.close_quote(start);
+                }
+            }
+            ("ul" | "ol", false) => {
+                if self.lists.is_empty() {
+                    self.block();
+                } else {
+                    self.end_line();
+                }
+                let start = tag.attr("start").and_then(|s| s.parse().ok()).unwrap_or(1);
+                self.lists.push((name == "ol").then_some(start));
+            }
+            ("ul" | "ol", true) => {
+                self.lists.pop();
+                self.fresh_item = false;
+                if self.lists.is_empty() {
+                    self.block();
+                } else {
+                    self.end_line();
+                }
+            }
+            ("li", false) => {
+                self.fresh_item = false;
+                self.end_line();
+                let depth = self.lists.len().max(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(next)) => {
+                        *next += 1;
+                        format!("{}. ", *next - 1)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.out.push_str(&"  ".repeat(depth - 1));
+                self.out.push_str(&marker);
+                self.pending_space = false;
+                self.suppress_space = true;
+                self.fresh_item = true;
+            }
+            ("li", true) => self.end_line(),
+            ("table", _) => {
+                self.block();
+                self.table_rows = 0;
+            }
+            ("tr", false) => {
+                self.end_line();
+                self.row_cells = 0;
+            }
+            ("tr", true) => self.close_row(),
+            ("td" | "th", false) => {
+                self.content();
+                if self.row_cells > 0 {
+                    self.out.push_str(" | ");
+                } else if self.markdown {
+                    self.out.push_str("| ");
+                }
+                self.row_cells += 1;
+                self.suppress_space = true;
+            }
+            _ => {}
+        }
+    }
+
+    fn emphasis(&mut self, end: bool, marker: &str) {
+        if !self.markdown {
+            return;
+        }
+        if end {
+            self.close_marker(marker);
+        } else {
+            self.open_marker(marker);
+        }
+    }
+
+    fn close_pre(&mut self) {
+        if !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+        if self.markdown {
+            self.out.push_str("```");
+        }
+        self.block();
+    }
+
+    fn close_link(&mut self, start: usize, href: Option<String>) {
+        let start = start.min(self.out.len());
+        let text = self.out[start..]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.out.truncate(start);
+        // Links without text, such as icons, are dropped
+        if text.is_empty() {
+            return;
+        }
+        let href = href.filter(|h| !h.starts_with('#') && !h.starts_with("javascript:"));
+        match href {
+            Some(href) if self.markdown => self.out.push_str(&format!("[{text}]({href})")),
+            Some(href) if href != text && !text.contains(&href) => {
+                self.out.push_str(&format!("{text} ({href})"))
+            }
+            _ => self.out.push_str(&text),
+        }
+    }
+
+    fn close_quote(&mut self, start: usize) {
+        let start = start.min(self.out.len());
+        let quoted = self.out[start..].trim().to_string();
+        self.out.truncate(start);
+        if quoted.is_empty() {
+            return;
+        }
+        for line in quoted.lines() {
+            self.out.push_str(if line.is_empty() { ">" } else { "> " });
+            self.out.push_str(line);
+            self.out.push('\n');
+        }
+        self.block();
+    }
+
+    fn close_row(&mut self) {
+        if self.row_cells == 0 {
+            return;
+        }
+        if self.markdown {
+            self.out.push_str(" |");
+            if self.table_rows == 0 {
+                self.out.push('\n');
+                self.out.push('|');
+                self.out.push_str(&" --- |".repeat(self.row_cells));
+            }
+        }
+        self.table_rows += 1;
+        self.row_cells = 0;
+        self.end_line();
+    }
+
+    fn image(&mut self, tag: &Tag) {
+        if is_tracking_pixel(tag) {
+            return;
+        }
+        let alt = tag
+            .attr("alt")
+            .or_else(|| tag.attr("title"))
+            .map(|alt| alt.split_whitespace().collect::<Vec<_>>().join(" "))
+            .unwrap_or_default();
+        // Inline data would only waste space
+        let src = tag
+            .attr("src")
+            .or_else(|| tag.attr("data-src"))
+            .filter(|src| !src.starts_with("data:"));
+        match src {
+            Some(src) if self.markdown => {
+                self.content();
+                self.out.push_str(&format!("![{alt}]({src})"));
+            }
+            _ if !alt.is_empty() => {
+                self.content();
+                if self.markdown {
+                    self.out.push_str(&alt);
+                } else {
+                    self.out.push_str(&format!("[image: {alt}]"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Embedded players become a link to their source
+    fn embed(&mut self, tag: &Tag) {
+        let Some(src) = tag.attr("src") else {
+            return;
+        };
+        let label = tag.attr("title").unwrap_or(match tag.name.as_str() {
+            "video" => "Video",
+            "audio" => "Audio",
+            _ => "Embedded content",
+        });
+        self.block();
+        self.content();
+        if self.markdown {
+            self.out.push_str(&format!("[{label}]({src})"));
+        } else {
+            self.out.push_str(&format!("{label}: {src}"));
+        }
+        self.block();
+    }
+
+    fn finish(mut self) -> String {
+        while let Some((start, href)) = self.links.pop() {
+            self.close_link(start, href);
+        }
+        while let Some(start) = self.quotes.pop() {
+            self.close_quote(start);
+        }
+        if self.pre > 0 {
+            self.close_pre();
+        }
+        let mut out = String::with_capacity(self.out.len());
+        let mut blank = false;
+        for line in self.out.lines().map(str::trim_end) {
+            if line.is_empty() {
+                blank = !out.is_empty();
+                continue;
+            }
+            if blank {
+                out.push('\n');
+                blank = false;
+            }
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.truncate(out.trim_end().len());
+        out
+    }
+}
+
+/// Images of at most one pixel or hidden ones, as used to track readers
+fn is_tracking_pixel(tag: &Tag) -> bool {
+    let tiny = |name: &str| {
+        tag.attr(name)
+            .and_then(|v| v.trim_end_matches("px").trim().parse::<f64>().ok())
+            .is_some_and(|v| v <= 1.0)
+    };
+    let style: String = tag
+        .attr("style")
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    tiny("width")
+        || tiny("height")
+        || style.contains("display:none")
+        || style.contains("visibility:hidden")
+}
+
+/// The tag at the start of `html` and its length, or `None` if `<` starts no tag.
+fn parse_tag(html: &str) -> Option<(Tag, usize)> {
+    let bytes = html.as_bytes();
+    let end = bytes.get(1) == Some(&b'/');
+    let mut i = if end { 2 } else { 1 };
+    let name_start = i;
+    if !bytes.get(i)?.is_ascii_alphabetic() {
+        return None;
+    }
+    while bytes
+        .get(i)
+        .is_some_and(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b':'))
+    {
+        i += 1;
+    }
+    let name = html[name_start..i].to_ascii_lowercase();
+    let mut attrs = Vec::new();
+    loop {
+        while bytes
+            .get(i)
+            .is_some_and(|b| b.is_ascii_whitespace() || *b == b'/')
+        {
+            i += 1;
+        }
+        if *bytes.get(i)? == b'>' {
+            let self_closing = bytes[i - 1] == b'/';
+            let tag = Tag {
+                name,
+                end,
+                self_closing,
+                attrs,
+            };
+            return Some((tag, i + 1));
+        }
+        let attr_start = i;
+        while bytes
+            .get(i)
+            .is_some_and(|b| !b.is_ascii_whitespace() && !matches!(b, b'=' | b'>' | b'/'))
+        {
+            i += 1;
+        }
+        let attr = html[attr_start..i].to_ascii_lowercase();
+        while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+            i += 1;
+        }
+        let mut value = String::new();
+        if bytes.get(i) == Some(&b'=') {
+            i += 1;
+            while bytes.get(i).is_some_and(u8::is_ascii_whitespace) {
+                i += 1;
+            }
+            match *bytes.get(i)? {
+                quote @ (b'"' | b'\'') => {
+                    let start = i + 1;
+                    let len = html[start..].find(char::from(quote))?;
+                    value = decode_entities(&html[start..start + len]).into_owned();
+                    i = start + len + 1;
+                }
+                _ => {
+                    let start = i;
+                    while bytes
+                        .get(i)
+                        .is_some_and(|b| !b.is_ascii_whitespace() && *b != b'>')
+                    {
+                        i += 1;
+                    }
+                    value = decode_entities(&html[start..i]).into_owned();
+                }
+            }
+        }
+        if !attr.is_empty() {
+            attrs.push((attr, value));
+        }
+    }
+}
+
+/// `html` after the end tag of the element `name`, or nothing if it is not closed.
+fn skip_element<'a>(html: &'a str, name: &str) -> &'a str {
+    let lower = html.to_ascii_lowercase();
+    let Some(start) = lower.find(&format!("</{name}")) else {
+        return "";
+    };
+    html[start..]
+        .find('>')
+        .map_or("", |end| &html[start + end + 1..])
+}
+
+/// `text` with character references and the common named entities replaced.
+fn decode_entities(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        match entity(rest) {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+/// The character of the reference at the start of `text` and its length.
+fn entity(text: &str) -> Option<(char, usize)> {
+    let semi = text.bytes().take(12).position(|b| b == b';')?;
+    let name = &text[1..semi];
+    let c = match name.strip_prefix('#') {
+        Some(number) => {
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)?
+        }
+        None => match name {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            "nbsp" | "ensp" | "emsp" | "thinsp" => ' ',
+            "ndash" => '–',
+            "mdash" => '—',
+            "hellip" => '…',
+            "lsquo" => '‘',
+            "rsquo" => '’',
+            "ldquo" => '“',
+            "rdquo" => '”',
+            "laquo" => '«',
+            "raquo" => '»',
+            "middot" => '·',
+            "bull" => '•',
+            "times" => '×',
+            "copy" => '©',
+            "reg" => '®',
+            "trade" => '™',
+            "deg" => '°',
+            "yen" => '¥',
+            "euro" => '€',
+            _ => return None,
+        },
+    };
+    Some((c, semi + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = r#"<div class="post" style="color:red">
+        <h2>Windows 11 <em>August</em> update</h2>
+        <p>Microsoft released the <b>cumulative update</b> &mdash; see the
+        <a href="https://support.microsoft.com/kb/5063878" target="_blank">release notes</a>.</p>
+        <p><img src="https://img.ithome.com/873001.jpg" alt="Settings  page"><br>
+        <img src="https://track.example.com/pixel.gif" width="1" height="1"></p>
+        <script>document.write("ad")</script><style>.post{}</style>
+        <ul><li>Fixes <code>explorer.exe</code> crashes</li><li>Faster start menu</li></ul>
+        <blockquote><p>Rolling out to everyone today.</p></blockquote>
+        <iframe src="https://player.bilibili.com/player.html?bvid=BV1" title="Demo video">Fallback</iframe>
+        <a href="https://www.ithome.com/"><img src="data:image/png;base64,iVBORw0" alt=""></a>
+    </div>"#;
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(
+            html_to_markdown(ARTICLE),
+            "## Windows 11 _August_ update\n\
+             \n\
+             Microsoft released the **cumulative update** — see the \
+             [release notes](https://support.microsoft.com/kb/5063878).\n\
+             \n\
+             ![Settings page](https://img.ithome.com/873001.jpg)\n\
+             \n\
+             - Fixes `explorer.exe` crashes\n\
+             - Faster start menu\n\
+             \n\
+             > Rolling out to everyone today.\n\
+             \n\
+             [Demo video](https://player.bilibili.com/player.html?bvid=BV1)"
+        );
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(
+            html_to_text(ARTICLE),
+            "Windows 11 August update\n\
+             \n\
+             Microsoft released the cumulative update — see the release notes \
+             (https://support.microsoft.com/kb/5063878).\n\
+             \n\
+             [image: Settings page]\n\
+             \n\
+             - Fixes explorer.exe crashes\n\
+             - Faster start menu\n\
+             \n\
+             > Rolling out to everyone today.\n\
+             \n\
+             Demo video: https://player.bilibili.com/player.html?bvid=BV1"
+        );
+    }
+
+    #[test]
+    fn test_nested_lists_tables_and_code() {
+        let html = "<ol start=\"3\"><li>One<ul><li>Sub</li></ul></li><li>Two</li></ol>\
+            <table><tr><th>Route</th><th>Items</th></tr><tr><td>ithome</td><td>20</td></tr></table>\
+            <pre><code>let a = 1;\n  a &lt; 2</code></pre>";
+        assert_eq!(
+            html_to_markdown(html),
+            "3. One\n  - Sub\n4. Two\n\n| Route | Items |\n| --- | --- |\n| ithome | 20 |\n\n```\nlet a = 1;\n  a < 2\n```"
+        );
+    }
+
+    #[test]
+    fn test_malformed_html_and_entities() {
+        assert_eq!(
+            html_to_text("a < b &amp;&amp; c &unknown; &#x4e2d;&#25991;"),
+            "a < b && c &unknown; 中文"
+        );
+        assert_eq!(
+            html_to_text("<p>unclosed <b>bold <a href=x>link"),
+            "unclosed bold link (x)"
+        );
+        assert_eq!(
+            html_to_markdown("<b></b><a href=\"#top\">top</a><!-- note -->"),
+            "top"
+        );
+        assert_eq!(html_to_text("plain text"), "plain text");
+        assert_eq!(
+            "MARKDOWN".parse::<ContentFormat>().unwrap(),
+            ContentFormat::Markdown
+        );
+        assert_eq!(
+            "rtf".parse::<ContentFormat>().unwrap_err().to_string(),
+            "unknown content format `rtf`, expected one of: html, markdown, text"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Stable identities of feed items, so that the same entry can be recognized across
+//! fetches, and deduplication of item lists by them.
+
+use std::collections::HashSet;
+
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::{FeedItem, FeedResponse};
+
+/// Prefix of the query parameters analytics tools append to shared links
+const TRACKING_PARAM_PREFIX: &str = "utm_";
+
+/// `link` without what varies between shares of the same page: `utm_*` query parameters
+/// and the fragment are removed and the host is lowercased.
+///
+/// Links that are not absolute `http` or `https` URLs are only trimmed; `None` for an
+/// empty link.
+pub fn canonicalize_link(link: &str) -> Option<String> {
+    let link = link.trim();
+    if link.is_empty() {
+        return None;
+    }
+    let Ok(mut url) = Url::parse(link) else {
+        return Some(link.to_string());
+    };
+    if !matches!(url.scheme(), "http" | "https") {
+        return Some(link.to_string());
+    }
+    // The URL parser already lowercases the host of http(s) URLs
+    url.set_fragment(None);
+    if url.query().is_some() {
+        let kept: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(name, _)| !name.to_ascii_lowercase().starts_with(TRACKING_PARAM_PREFIX))
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect();
+        if kept.is_empty() {
+            url.set_query(None);
+        } else {
+            url.query_pairs_mut().clear().extend_pairs(kept);
+        }
+    }
+    Some(url.into())
+}
+
+impl FeedItem {
+    /// Identity of the item that stays the same across fetches.
+    ///
+    /// This is the GUID, the canonical link when there is none (see [`canonicalize_link`]),
+    /// or else a hash of title, description and date prefixed with `sha256:`. GUIDs that
+    /// are permalinks are canonicalized like links.
+    pub fn item_id(&self) -> String {
+        self.guid
+            .as_deref()
+            .and_then(canonicalize_link)
+            .or_else(|| canonicalize_link(&self.link))
+            .unwrap_or_else(|| self.content_hash())
+    }
+
+    fn content_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        for part in [
+            self.title.as_str(),
+            self.description.as_str(),
+            self.pub_date.as_deref().unwrap_or_default(),
+        ] {
+            hasher.update(part.as_bytes());
+            // Separator so that moving text between fields changes the hash
+            hasher.update([0]);
+        }
+        format!("sha256:{}", hex::encode(hasher.finalize()))
+    }
+}
+
+/// `items` with every item whose [`FeedItem::item_id`] was seen before removed, in their
+/// original order.
+///
+/// To merge the items of successive fetches, chain the lists with the preferred version
+/// of an entry, usually the newest fetch, first.
+pub fn dedupe_items(items: impl IntoIterator<Item = FeedItem>) -> Vec<FeedItem> {
+    let mut seen = HashSet::new();
+    items
+        .into_iter()
+        .filter(|item| seen.insert(item.item_id()))
+        .collect()
+}
+
+impl FeedResponse {
+    /// Remove the items repeating an earlier one, and return how many were removed.
+    pub fn dedupe(&mut self) -> usize {
+        let before = self.items.len();
+        self.items = dedupe_items(std::mem::take(&mut self.items));
+        before - self.items.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_links_are_canonicalized() {
+        assert_eq!(
+            canonicalize_link(
+                " https://WWW.IThome.com/0/873/001.htm?utm_source=rss&id=7&UTM_Medium=feed#comments "
+            )
+            .as_deref(),
+            Some("https://www.ithome.com/0/873/001.htm?id=7")
+        );
+        assert_eq!(
+            canonicalize_link("https://www.ithome.com/0/873/001.htm?utm_source=rss").as_deref(),
+            Some("https://www.ithome.com/0/873/001.htm")
+        );
+        assert_eq!(
+            canonicalize_link("magnet:?xt=urn:btih:c9e15763&dn=x").as_deref(),
+            Some("magnet:?xt=urn:btih:c9e15763&dn=x")
+        );
+        assert_eq!(canonicalize_link("66b0d3c2").as_deref(), Some("66b0d3c2"));
+        assert_eq!(canonicalize_link("  "), None);
+    }
+
+    #[test]
+    fn test_item_ids_and_dedupe() {
+        let item = |guid: Option<&str>, link: &str, title: &str| FeedItem {
+            title: title.to_string(),
+            link: link.to_string(),
+            guid: guid.map(str::to_string),
+            ..FeedItem::default()
+        };
+        let by_guid = item(Some("66b0d3c2"), "https://example.com/a", "A");
+        assert_eq!(by_guid.item_id(), "66b0d3c2");
+        let by_link = item(None, "https://example.com/a?utm_campaign=x#top", "A");
+        assert_eq!(by_link.item_id(), "https://example.com/a");
+        let by_hash = item(None, "", "A");
+        assert!(by_hash.item_id().starts_with("sha256:"));
+        assert_eq!(by_hash.item_id(), item(None, "", "A").item_id());
+        assert_ne!(by_hash.item_id(), item(None, "", "B").item_id());
+
+        let newest = vec![
+            item(None, "https://example.com/b", "B, edited"),
+            item(None, "https://example.com/a", "A"),
+        ];
+        let previous = vec![
+            item(None, "https://example.com/b?utm_source=rss", "B"),
+            item(None, "https://example.com/c", "C"),
+        ];
+        let merged = dedupe_items(newest.into_iter().chain(previous));
+        let titles: Vec<&str> = merged.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, ["B, edited", "A", "C"]);
+
+        let mut feed = FeedResponse {
+            items: vec![by_link.clone(), by_hash, by_link],
+            ..FeedResponse::default()
+        };
+        assert_eq!(feed.dedupe(), 1);
+        assert_eq!(feed.items.len(), 2);
+    }
+}
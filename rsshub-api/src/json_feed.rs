@@ -0,0 +1,162 @@
+//! Mapping of JSON Feed 1.0 / 1.1 documents, e.g. RSSHub's `?format=json`, into
+//! [`FeedResponse`].
+
+use serde::Deserialize;
+
+use crate::{media, Enclosure, FeedChannel, FeedItem, FeedResponse, PodcastEpisode};
+
+#[derive(Deserialize)]
+struct JsonFeed {
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    home_page_url: Option<String>,
+    #[serde(default)]
+    feed_url: Option<String>,
+    /// JSON Feed 1.1
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    icon: Option<String>,
+    #[serde(default)]
+    favicon: Option<String>,
+    #[serde(default)]
+    items: Vec<JsonItem>,
+}
+
+#[derive(Deserialize)]
+struct JsonItem {
+    #[serde(default)]
+    id: Option<serde_json::Value>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    external_url: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    content_html: Option<String>,
+    #[serde(default)]
+    content_text: Option<String>,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    date_published: Option<String>,
+    #[serde(default)]
+    date_modified: Option<String>,
+    /// JSON Feed 1.1
+    #[serde(default)]
+    authors: Vec<JsonAuthor>,
+    /// JSON Feed 1.0
+    #[serde(default)]
+    author: Option<JsonAuthor>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    image: Option<String>,
+    #[serde(default)]
+    banner_image: Option<String>,
+    #[serde(default)]
+    attachments: Vec<JsonAttachment>,
+}
+
+#[derive(Deserialize)]
+struct JsonAttachment {
+    url: String,
+    #[serde(default)]
+    mime_type: Option<String>,
+    #[serde(default)]
+    size_in_bytes: Option<u64>,
+    #[serde(default)]
+    duration_in_seconds: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct JsonAuthor {
+    #[serde(default)]
+    name: Option<String>,
+}
+
+/// Whether the `Content-Type` announces JSON rather than XML.
+pub(crate) fn is_json_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+    mime.eq_ignore_ascii_case("application/feed+json")
+        || mime.eq_ignore_ascii_case("application/json")
+}
+
+/// Whether `content` looks like a JSON Feed document.
+pub(crate) fn is_json_feed(content: &str) -> bool {
+    content.trim_start().starts_with('{') && content.contains("jsonfeed.org/version/")
+}
+
+pub(crate) fn parse_json_feed(content: &str) -> Result<FeedResponse, serde_json::Error> {
+    let feed: JsonFeed = serde_json::from_str(content)?;
+    Ok(FeedResponse {
+        title: feed.title,
+        description: feed.description.unwrap_or_default(),
+        channel: FeedChannel {
+            link: feed.home_page_url,
+            self_link: feed.feed_url,
+            language: feed.language,
+            image: feed.icon.or(feed.favicon),
+            ..FeedChannel::default()
+        },
+        items: feed.items.into_iter().map(feed_item).collect(),
+        raw_content: Some(content.to_string()),
+        warnings: Vec::new(),
+    })
+}
+
+/// HTML content is preferred over plain text and the summary, and the publication
+/// date falls back to the modification date. The duration of the first attachment that
+/// has one becomes the podcast duration.
+fn feed_item(item: JsonItem) -> FeedItem {
+    let authors: Vec<String> = item
+        .authors
+        .into_iter()
+        .chain(item.author)
+        .filter_map(|a| a.name)
+        .collect();
+    // The spec requires a string, but some generators emit numbers
+    let guid = item.id.and_then(|id| match id {
+        serde_json::Value::String(s) => Some(s),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    });
+    let duration_secs = item
+        .attachments
+        .iter()
+        .find_map(|a| a.duration_in_seconds)
+        .filter(|secs| *secs >= 0.0)
+        .map(|secs| secs as u64);
+    media::complete(FeedItem {
+        title: item.title.unwrap_or_default(),
+        description: item
+            .content_html
+            .or(item.content_text)
+            .or(item.summary)
+            .unwrap_or_default(),
+        link: item.url.or(item.external_url).unwrap_or_default(),
+        pub_date: item.date_published.or_else(|| item.date_modified.clone()),
+        updated: item.date_modified,
+        author: (!authors.is_empty()).then(|| authors.join(", ")),
+        categories: item.tags,
+        guid,
+        enclosures: item
+            .attachments
+            .into_iter()
+            .map(|a| Enclosure {
+                url: a.url,
+                mime_type: a.mime_type,
+                length: a.size_in_bytes,
+            })
+            .collect(),
+        image: item.image.or(item.banner_image),
+        podcast: duration_secs.map(|secs| PodcastEpisode {
+            duration_secs: Some(secs),
+            ..PodcastEpisode::default()
+        }),
+        ..FeedItem::default()
+    })
+}
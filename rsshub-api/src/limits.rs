@@ -0,0 +1,86 @@
+//! Upper bounds on the size of upstream response bodies.
+
+use serde::Deserialize;
+
+use crate::error::{Result, RsshubError};
+
+const MIB: usize = 1024 * 1024;
+
+/// Largest error body read to build the excerpt in [`RsshubError::Http`].
+const ERROR_BODY_LIMIT: usize = 64 * 1024;
+
+/// Maximum body size in bytes accepted from each endpoint.
+///
+/// A response announcing a larger `Content-Length` is rejected before its body is read,
+/// and a body without one is aborted as soon as it grows past the limit; both fail with
+/// [`RsshubError::ResponseTooLarge`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct BodyLimits {
+    /// `/api/namespace`
+    pub namespaces: usize,
+    /// `/api/namespace/:namespace`
+    pub namespace: usize,
+    /// `/api/radar/rules`
+    pub radar_rules: usize,
+    /// `/api/radar/rules/:domain`
+    pub radar_rule: usize,
+    /// `/api/category/:category`
+    pub category: usize,
+    /// Feeds
+    pub feed: usize,
+}
+
+impl Default for BodyLimits {
+    fn default() -> Self {
+        Self {
+            namespaces: 32 * MIB,
+            namespace: 4 * MIB,
+            radar_rules: 16 * MIB,
+            radar_rule: MIB,
+            category: 16 * MIB,
+            feed: 8 * MIB,
+        }
+    }
+}
+
+/// Read the body of `response`, failing once it exceeds `limit` bytes.
+pub(crate) async fn read_body(
+    url: &str,
+    mut response: reqwest::Response,
+    limit: usize,
+) -> Result<Vec<u8>> {
+    let too_large = || RsshubError::ResponseTooLarge {
+        url: url.to_string(),
+        limit,
+    };
+    let announced = response.content_length();
+    if announced.is_some_and(|len| len > limit as u64) {
+        return Err(too_large());
+    }
+    let mut body = Vec::with_capacity(announced.unwrap_or(0) as usize);
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| RsshubError::from_reqwest(url, e))?
+    {
+        if body.len() + chunk.len() > limit {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Read at most the first 64 KiB of an error body, which is only kept as an excerpt.
+pub(crate) async fn read_error_body(mut response: reqwest::Response) -> String {
+    let mut body = Vec::new();
+    while body.len() < ERROR_BODY_LIMIT {
+        match response.chunk().await {
+            Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+            _ => break,
+        }
+    }
+    body.truncate(ERROR_BODY_LIMIT);
+    String::from_utf8_lossy(&body).into_owned()
+}
@@ -0,0 +1,138 @@
+//! Media attached to feed items: Media RSS images and thumbnails, podcast durations and
+//! BitTorrent links.
+
+use std::collections::BTreeMap;
+
+use crate::{Enclosure, FeedItem};
+
+/// MIME type RSSHub's BitTorrent routes give their enclosures.
+const BITTORRENT_MIME: &str = "application/x-bittorrent";
+
+/// An extension element as parsed by `rss` and `atom_syndication`, which have separate but
+/// identical types.
+pub(crate) trait Element: Sized {
+    fn attr(&self, name: &str) -> Option<&str>;
+    fn children(&self) -> &BTreeMap<String, Vec<Self>>;
+}
+
+impl Element for rss::extension::Extension {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs().get(name).map(String::as_str)
+    }
+
+    fn children(&self) -> &BTreeMap<String, Vec<Self>> {
+        rss::extension::Extension::children(self)
+    }
+}
+
+impl Element for atom_syndication::extension::Extension {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs().get(name).map(String::as_str)
+    }
+
+    fn children(&self) -> &BTreeMap<String, Vec<Self>> {
+        atom_syndication::extension::Extension::children(self)
+    }
+}
+
+/// Image and thumbnail URLs from the `media:` elements of an item.
+///
+/// `<media:content>` counts as the image when its `medium` or `type` says so; thumbnails
+/// may also be nested in `<media:content>` or `<media:group>`.
+pub(crate) fn media_images<E: Element>(
+    media: Option<&BTreeMap<String, Vec<E>>>,
+) -> (Option<String>, Option<String>) {
+    let Some(media) = media else {
+        return (None, None);
+    };
+    let image = media_elements(media, "content")
+        .into_iter()
+        .find(|e| {
+            e.attr("medium") == Some("image")
+                || e.attr("type").is_some_and(|t| t.starts_with("image/"))
+        })
+        .and_then(|e| e.attr("url"));
+    let thumbnail = media_elements(media, "thumbnail")
+        .into_iter()
+        .find_map(|e| e.attr("url"));
+    (image.map(str::to_string), thumbnail.map(str::to_string))
+}
+
+/// Elements called `name`, at the top level first, then inside other `media:` elements.
+fn media_elements<'a, E: Element>(media: &'a BTreeMap<String, Vec<E>>, name: &str) -> Vec<&'a E> {
+    let mut found: Vec<&E> = media.get(name).into_iter().flatten().collect();
+    for element in media.values().flatten() {
+        found.extend(media_elements(element.children(), name));
+    }
+    found
+}
+
+/// Seconds in an iTunes duration, given as `H:MM:SS`, `MM:SS` or plain seconds.
+pub(crate) fn parse_duration(duration: &str) -> Option<u64> {
+    let parts: Vec<&str> = duration.trim().split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    parts
+        .iter()
+        .enumerate()
+        .try_fold(0, |secs: u64, (i, part)| {
+            // Fractions of the last component are dropped
+            let part = if i == parts.len() - 1 {
+                part.split('.').next().unwrap_or_default()
+            } else {
+                part
+            };
+            Some(secs * 60 + part.parse::<u64>().ok()?)
+        })
+}
+
+/// Fill in the fields derived from the other ones: magnet and torrent links from the
+/// enclosures or the link, and the image from an image enclosure.
+pub(crate) fn complete(mut item: FeedItem) -> FeedItem {
+    let is_magnet = |url: &str| url.starts_with("magnet:");
+    let urls = || item.enclosures.iter().map(|e| e.url.as_str());
+    item.magnet = urls()
+        .chain([item.link.as_str()])
+        .find(|url| is_magnet(url))
+        .map(str::to_string);
+    item.torrent = item
+        .enclosures
+        .iter()
+        .find(|e| {
+            !is_magnet(&e.url)
+                && (e.mime_type.as_deref() == Some(BITTORRENT_MIME) || e.url.ends_with(".torrent"))
+        })
+        .map(|e| e.url.clone());
+    if item.image.is_none() {
+        item.image = item
+            .enclosures
+            .iter()
+            .find(|e| is_image(e))
+            .map(|e| e.url.clone());
+    }
+    item
+}
+
+fn is_image(enclosure: &Enclosure) -> bool {
+    enclosure
+        .mime_type
+        .as_deref()
+        .is_some_and(|t| t.starts_with("image/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_durations() {
+        assert_eq!(parse_duration("1:02:03"), Some(3723));
+        assert_eq!(parse_duration("42:10"), Some(2530));
+        assert_eq!(parse_duration("3600"), Some(3600));
+        assert_eq!(parse_duration("05:30.25"), Some(330));
+        assert_eq!(parse_duration("1:2:3:4"), None);
+        assert_eq!(parse_duration("an hour"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+}
@@ -0,0 +1,383 @@
+//! RSSHub's generic feed parameters, shared by every route.
+
+use std::{fmt, str::FromStr, time::Duration};
+
+use serde::Deserialize;
+
+use crate::error::ParseArgumentError;
+
+/// Generic parameters of a feed request: item limit, filters, sorting and the form of
+/// the item content.
+///
+/// RSSHub applies them after the route has run; the filters are regular expressions
+/// matched case-sensitively unless `filter_case_sensitive(false)` is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedQuery {
+    limit: Option<usize>,
+    filter: Option<String>,
+    filter_title: Option<String>,
+    filter_description: Option<String>,
+    filter_author: Option<String>,
+    filter_category: Option<String>,
+    filterout: Option<String>,
+    filterout_title: Option<String>,
+    filterout_description: Option<String>,
+    filterout_author: Option<String>,
+    filterout_category: Option<String>,
+    filter_time: Option<Duration>,
+    filter_case_sensitive: Option<bool>,
+    sorted: Option<bool>,
+    fulltext: bool,
+    brief: Option<usize>,
+    opencc: Option<OpenccConversion>,
+    format: Option<FeedFormat>,
+}
+
+/// Wire format of a feed, RSSHub's `format` parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedFormat {
+    /// RSS 2.0, RSSHub's default
+    Rss,
+    Atom,
+    /// JSON Feed 1.1
+    Json,
+}
+
+impl FeedFormat {
+    /// Value of the `format` parameter
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Rss => "rss",
+            Self::Atom => "atom",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Chinese script conversion applied by RSSHub's `opencc` parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpenccConversion {
+    /// Simplified to Traditional
+    S2t,
+    /// Traditional to Simplified
+    T2s,
+    /// Simplified to Taiwan Traditional
+    S2tw,
+    /// Taiwan Traditional to Simplified
+    Tw2s,
+    /// Simplified to Hong Kong Traditional
+    S2hk,
+    /// Hong Kong Traditional to Simplified
+    Hk2s,
+    /// Simplified to Taiwan Traditional with Taiwanese phrases
+    S2twp,
+    /// Taiwan Traditional with Taiwanese phrases to Simplified
+    Tw2sp,
+    /// Traditional to Taiwan Traditional
+    T2tw,
+    /// Traditional to Hong Kong Traditional
+    T2hk,
+}
+
+impl OpenccConversion {
+    pub const ALL: [Self; 10] = [
+        Self::S2t,
+        Self::T2s,
+        Self::S2tw,
+        Self::Tw2s,
+        Self::S2hk,
+        Self::Hk2s,
+        Self::S2twp,
+        Self::Tw2sp,
+        Self::T2tw,
+        Self::T2hk,
+    ];
+
+    /// Value of the `opencc` parameter, e.g. `s2t`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::S2t => "s2t",
+            Self::T2s => "t2s",
+            Self::S2tw => "s2tw",
+            Self::Tw2s => "tw2s",
+            Self::S2hk => "s2hk",
+            Self::Hk2s => "hk2s",
+            Self::S2twp => "s2twp",
+            Self::Tw2sp => "tw2sp",
+            Self::T2tw => "t2tw",
+            Self::T2hk => "t2hk",
+        }
+    }
+}
+
+impl fmt::Display for OpenccConversion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OpenccConversion {
+    type Err = ParseArgumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseArgumentError {
+                argument: "OpenCC conversion",
+                value: s.to_string(),
+                expected: Self::ALL.map(Self::as_str).to_vec(),
+            })
+    }
+}
+
+impl FeedQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep at most `limit` items
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Keep items whose title or description matches `pattern`
+    pub fn filter(mut self, pattern: impl Into<String>) -> Self {
+        self.filter = Some(pattern.into());
+        self
+    }
+
+    pub fn filter_title(mut self, pattern: impl Into<String>) -> Self {
+        self.filter_title = Some(pattern.into());
+        self
+    }
+
+    pub fn filter_description(mut self, pattern: impl Into<String>) -> Self {
+        self.filter_description = Some(pattern.into());
+        self
+    }
+
+    pub fn filter_author(mut self, pattern: impl Into<String>) -> Self {
+        self.filter_author = Some(pattern.into());
+        self
+    }
+
+    pub fn filter_category(mut self, pattern: impl Into<String>) -> Self {
+        self.filter_category = Some(pattern.into());
+        self
+    }
+
+    /// Drop items whose title or description matches `pattern`
+    pub fn filterout(mut self, pattern: impl Into<String>) -> Self {
+        self.filterout = Some(pattern.into());
+        self
+    }
+
+    pub fn filterout_title(mut self, pattern: impl Into<String>) -> Self {
+        self.filterout_title = Some(pattern.into());
+        self
+    }
+
+    pub fn filterout_description(mut self, pattern: impl Into<String>) -> Self {
+        self.filterout_description = Some(pattern.into());
+        self
+    }
+
+    pub fn filterout_author(mut self, pattern: impl Into<String>) -> Self {
+        self.filterout_author = Some(pattern.into());
+        self
+    }
+
+    pub fn filterout_category(mut self, pattern: impl Into<String>) -> Self {
+        self.filterout_category = Some(pattern.into());
+        self
+    }
+
+    /// Keep items published within `max_age`; sent in whole seconds
+    pub fn filter_time(mut self, max_age: Duration) -> Self {
+        self.filter_time = Some(max_age);
+        self
+    }
+
+    /// Match the filters case-sensitively; RSSHub defaults to `true`
+    pub fn filter_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.filter_case_sensitive = Some(case_sensitive);
+        self
+    }
+
+    /// `false` keeps the route's item order instead of sorting by date
+    pub fn sorted(mut self, sorted: bool) -> Self {
+        self.sorted = Some(sorted);
+        self
+    }
+
+    /// Ask for the full article content of each item (`mode=fulltext`), for routes whose
+    /// items otherwise only carry a summary
+    pub fn fulltext(mut self, fulltext: bool) -> Self {
+        self.fulltext = fulltext;
+        self
+    }
+
+    /// Reduce each item description to plain text of about `chars` characters; RSSHub
+    /// requires at least 100
+    pub fn brief(mut self, chars: usize) -> Self {
+        self.brief = Some(chars);
+        self
+    }
+
+    /// Convert titles and descriptions between Simplified and Traditional Chinese
+    pub fn opencc(mut self, conversion: OpenccConversion) -> Self {
+        self.opencc = Some(conversion);
+        self
+    }
+
+    /// Ask for this wire format instead of the client's default
+    pub fn format(mut self, format: FeedFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn is_fulltext(&self) -> bool {
+        self.fulltext
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The set parameters as a URL-encoded query string, without a leading `?`.
+    pub fn to_query_string(&self) -> String {
+        let patterns = [
+            ("filter", &self.filter),
+            ("filter_title", &self.filter_title),
+            ("filter_description", &self.filter_description),
+            ("filter_author", &self.filter_author),
+            ("filter_category", &self.filter_category),
+            ("filterout", &self.filterout),
+            ("filterout_title", &self.filterout_title),
+            ("filterout_description", &self.filterout_description),
+            ("filterout_author", &self.filterout_author),
+            ("filterout_category", &self.filterout_category),
+        ];
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        if let Some(limit) = self.limit {
+            query.append_pair("limit", &limit.to_string());
+        }
+        for (name, pattern) in patterns {
+            if let Some(pattern) = pattern {
+                query.append_pair(name, pattern);
+            }
+        }
+        if let Some(max_age) = self.filter_time {
+            query.append_pair("filter_time", &max_age.as_secs().to_string());
+        }
+        if let Some(case_sensitive) = self.filter_case_sensitive {
+            query.append_pair("filter_case_sensitive", &case_sensitive.to_string());
+        }
+        if let Some(sorted) = self.sorted {
+            query.append_pair("sorted", &sorted.to_string());
+        }
+        if self.fulltext {
+            query.append_pair("mode", "fulltext");
+        }
+        if let Some(chars) = self.brief {
+            query.append_pair("brief", &chars.to_string());
+        }
+        if let Some(conversion) = self.opencc {
+            query.append_pair("opencc", conversion.as_str());
+        }
+        if let Some(format) = self.format {
+            query.append_pair("format", format.as_str());
+        }
+        query.finish()
+    }
+
+    /// `path` with the parameters appended to any query string it already has.
+    pub fn apply(&self, path: &str) -> String {
+        let query = self.to_query_string();
+        if query.is_empty() {
+            path.to_string()
+        } else if path.contains('?') {
+            format!("{path}&{query}")
+        } else {
+            format!("{path}?{query}")
+        }
+    }
+}
+
+/// `path` asking for `format`, unless its query string already has a `format` parameter.
+pub(crate) fn with_default_format(path: &str, format: FeedFormat) -> String {
+    let has_format = path.split_once('?').is_some_and(|(_, query)| {
+        url::form_urlencoded::parse(query.as_bytes()).any(|(name, _)| name == "format")
+    });
+    if has_format {
+        path.to_string()
+    } else {
+        FeedQuery::new().format(format).apply(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameters_are_encoded() {
+        let query = FeedQuery::new()
+            .limit(5)
+            .filter("a b&c")
+            .filterout_author("bot$")
+            .filter_time(Duration::from_secs(3600))
+            .filter_case_sensitive(false)
+            .sorted(false);
+        assert_eq!(
+            query.to_query_string(),
+            "limit=5&filter=a+b%26c&filterout_author=bot%24&filter_time=3600\
+             &filter_case_sensitive=false&sorted=false"
+        );
+    }
+
+    #[test]
+    fn test_apply_keeps_existing_query() {
+        let query = FeedQuery::new().limit(3);
+        assert_eq!(query.apply("twitter/user/x"), "twitter/user/x?limit=3");
+        assert_eq!(
+            query.apply("twitter/user/x?format=atom"),
+            "twitter/user/x?format=atom&limit=3"
+        );
+        assert!(FeedQuery::new().is_empty());
+        assert_eq!(FeedQuery::new().apply("a/b"), "a/b");
+    }
+
+    #[test]
+    fn test_content_options_are_encoded() {
+        let query = FeedQuery::new()
+            .fulltext(true)
+            .brief(200)
+            .opencc("S2TW".parse().unwrap());
+        assert_eq!(
+            query.to_query_string(),
+            "mode=fulltext&brief=200&opencc=s2tw"
+        );
+        assert!(FeedQuery::new().fulltext(false).is_empty());
+        let err = "s2x".parse::<OpenccConversion>().unwrap_err();
+        assert_eq!(err.value, "s2x");
+        assert!(err
+            .to_string()
+            .starts_with("unknown OpenCC conversion `s2x`, expected one of: s2t,"));
+    }
+
+    #[test]
+    fn test_default_format_keeps_explicit_one() {
+        assert_eq!(
+            with_default_format("ithome/it?limit=5", FeedFormat::Json),
+            "ithome/it?limit=5&format=json"
+        );
+        assert_eq!(
+            with_default_format("ithome/it?format=atom", FeedFormat::Json),
+            "ithome/it?format=atom"
+        );
+    }
+}
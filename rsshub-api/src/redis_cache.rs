@@ -0,0 +1,326 @@
+//! Redis backend of the response cache (feature `redis`).
+
+use std::{fmt, time::Duration};
+
+use async_trait::async_trait;
+use redis::aio::{ConnectionManager, ConnectionManagerConfig};
+use tokio::sync::OnceCell;
+use tracing::warn;
+
+use crate::{
+    auth::redact_url,
+    cache::{CacheBackend, CacheEntry, CacheStats, StoredEntry},
+    error::{Result, RsshubError},
+};
+
+/// Redis backend, so that several processes share one warm cache.
+///
+/// Entries are stored as JSON under `key_prefix` followed by the request URL and expire
+/// in Redis after `expiry`. The connection is opened on first use and re-established
+/// after failures; Redis errors are logged and treated as misses, and each command is
+/// bounded by `timeout` so a slow Redis does not stall requests. Redis does not report
+/// per-client sizes, so `stats` are all zero.
+pub struct RedisCache {
+    client: redis::Client,
+    url: String,
+    connection: OnceCell<ConnectionManager>,
+    key_prefix: String,
+    expiry: Duration,
+    timeout: Duration,
+}
+
+impl fmt::Debug for RedisCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedisCache")
+            .field("url", &self.url)
+            .field("key_prefix", &self.key_prefix)
+            .field("expiry", &self.expiry)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RedisCache {
+    /// Cache on the Redis server at `url`, e.g. `redis://:password@cache.internal:6379/2`.
+    ///
+    /// Fails with [`RsshubError::InvalidConfig`] if `url` is not a Redis URL; the server
+    /// is not contacted yet.
+    pub fn new(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url).map_err(|e| RsshubError::InvalidConfig {
+            field: "redis_url".to_string(),
+            message: e.to_string(),
+        })?;
+        Ok(Self {
+            client,
+            url: redact_url(url),
+            connection: OnceCell::new(),
+            key_prefix: "rsshub-api:".to_string(),
+            expiry: Duration::from_secs(24 * 60 * 60),
+            timeout: Duration::from_secs(1),
+        })
+    }
+
+    /// Prefix of the Redis keys; defaults to `rsshub-api:`.
+    pub fn with_key_prefix(mut self, key_prefix: impl Into<String>) -> Self {
+        self.key_prefix = key_prefix.into();
+        self
+    }
+
+    /// How long Redis keeps an entry after it was last stored; defaults to one day.
+    pub fn with_expiry(mut self, expiry: Duration) -> Self {
+        self.expiry = expiry;
+        self
+    }
+
+    /// Connect and response timeout of Redis commands; defaults to one second.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn redis_key(&self, key: &str) -> String {
+        format!("{}{key}", self.key_prefix)
+    }
+
+    async fn connection(&self) -> Option<ConnectionManager> {
+        let config = ConnectionManagerConfig::new()
+            .set_number_of_retries(0)
+            .set_connection_timeout(self.timeout)
+            .set_response_timeout(self.timeout);
+        let connect = ConnectionManager::new_with_config(self.client.clone(), config);
+        match self.connection.get_or_try_init(|| connect).await {
+            Ok(connection) => Some(connection.clone()),
+            Err(e) => {
+                warn!("Failed to connect to Redis cache {}: {e}", self.url);
+                None
+            }
+        }
+    }
+
+    async fn query<T: redis::FromRedisValue>(&self, cmd: &redis::Cmd) -> Option<T> {
+        let mut connection = self.connection().await?;
+        match cmd.query_async(&mut connection).await {
+            Ok(value) => Some(value),
+            Err(e) => {
+                warn!("Redis cache {} failed: {e}", self.url);
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl CacheBackend for RedisCache {
+    async fn get(&self, key: &str) -> Option<CacheEntry> {
+        let mut cmd = redis::cmd("GET");
+        cmd.arg(self.redis_key(key));
+        let bytes: Vec<u8> = self.query::<Option<Vec<u8>>>(&cmd).await??;
+        match serde_json::from_slice::<StoredEntry>(&bytes) {
+            Ok(entry) => entry.into_entry(key),
+            Err(e) => {
+                warn!("Ignoring corrupted Redis cache entry for {key}: {e}");
+                None
+            }
+        }
+    }
+
+    async fn put(&self, key: &str, entry: CacheEntry) {
+        let bytes = match serde_json::to_vec(&StoredEntry::new(key, &entry)) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warn!("Failed to serialize cache entry for {key}: {e}");
+                return;
+            }
+        };
+        let mut cmd = redis::cmd("SET");
+        cmd.arg(self.redis_key(key))
+            .arg(bytes)
+            .arg("EX")
+            .arg(self.expiry.as_secs().max(1));
+        self.query::<()>(&cmd).await;
+    }
+
+    async fn invalidate(&self, key: &str) {
+        let mut cmd = redis::cmd("DEL");
+        cmd.arg(self.redis_key(key));
+        self.query::<()>(&cmd).await;
+    }
+
+    fn stats(&self) -> CacheStats {
+        CacheStats::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::SystemTime,
+    };
+
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::*;
+    use crate::cache::Validators;
+
+    type Store = Arc<Mutex<HashMap<Vec<u8>, (Vec<u8>, Option<u64>)>>>;
+
+    /// Minimal stand-in for redis-server speaking RESP2: GET, SET with EX, and DEL;
+    /// anything else, like the `CLIENT SETINFO` sent on connect, is answered with OK.
+    async fn fake_redis() -> (String, Store) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        let store = Store::default();
+        let shared = store.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(serve(socket, shared.clone()));
+            }
+        });
+        (url, store)
+    }
+
+    async fn serve(socket: TcpStream, store: Store) {
+        let (read, mut write) = socket.into_split();
+        let mut read = BufReader::new(read);
+        while let Some(args) = read_command(&mut read).await {
+            let reply = match (args[0].to_ascii_uppercase().as_slice(), &args[1..]) {
+                (b"GET", [key]) => match store.lock().unwrap().get(key) {
+                    Some((value, _)) => {
+                        let mut reply = format!("${}\r\n", value.len()).into_bytes();
+                        reply.extend_from_slice(value);
+                        reply.extend_from_slice(b"\r\n");
+                        reply
+                    }
+                    None => b"$-1\r\n".to_vec(),
+                },
+                (b"SET", [key, value, rest @ ..]) => {
+                    let ex = match rest {
+                        [flag, secs] if flag.eq_ignore_ascii_case(b"EX") => {
+                            String::from_utf8_lossy(secs).parse().ok()
+                        }
+                        _ => None,
+                    };
+                    store
+                        .lock()
+                        .unwrap()
+                        .insert(key.clone(), (value.clone(), ex));
+                    b"+OK\r\n".to_vec()
+                }
+                (b"DEL", keys) => {
+                    let mut store = store.lock().unwrap();
+                    let removed = keys.iter().filter(|k| store.remove(*k).is_some()).count();
+                    format!(":{removed}\r\n").into_bytes()
+                }
+                _ => b"+OK\r\n".to_vec(),
+            };
+            if write.write_all(&reply).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    async fn read_command(
+        read: &mut BufReader<tokio::net::tcp::OwnedReadHalf>,
+    ) -> Option<Vec<Vec<u8>>> {
+        let mut line = String::new();
+        read.read_line(&mut line).await.ok()?;
+        let count: usize = line.trim().strip_prefix('*')?.parse().ok()?;
+        let mut args = Vec::with_capacity(count);
+        for _ in 0..count {
+            line.clear();
+            read.read_line(&mut line).await.ok()?;
+            let len: usize = line.trim().strip_prefix('$')?.parse().ok()?;
+            let mut arg = vec![0; len + 2];
+            read.read_exact(&mut arg).await.ok()?;
+            arg.truncate(len);
+            args.push(arg);
+        }
+        Some(args)
+    }
+
+    fn entry() -> CacheEntry {
+        CacheEntry {
+            value: serde_json::json!({"example": {"routes": {}}}),
+            fetched_at: SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+            validators: Validators {
+                etag: Some("\"abc\"".to_string()),
+                last_modified: None,
+            },
+            body_len: 42,
+            parse_time: Duration::from_micros(7),
+            host: Some("https://rsshub.example".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_through_redis() {
+        let (url, store) = fake_redis().await;
+        let cache = RedisCache::new(&url)
+            .unwrap()
+            .with_key_prefix("test:")
+            .with_expiry(Duration::from_secs(600));
+        let key = "https://rsshub.example/api/namespace";
+
+        assert!(cache.get(key).await.is_none());
+        cache.put(key, entry()).await;
+        assert_eq!(cache.get(key).await, Some(entry()));
+        {
+            let store = store.lock().unwrap();
+            let (_, ex) = &store[format!("test:{key}").as_bytes()];
+            assert_eq!(*ex, Some(600));
+        }
+
+        // A second process sees the same entry
+        let other = RedisCache::new(&url).unwrap().with_key_prefix("test:");
+        assert_eq!(other.get(key).await, Some(entry()));
+        other.invalidate(key).await;
+        assert!(cache.get(key).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_redis_is_a_miss() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("redis://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let cache = RedisCache::new(&url)
+            .unwrap()
+            .with_timeout(Duration::from_millis(200));
+        cache.put("key", entry()).await;
+        assert!(cache.get("key").await.is_none());
+
+        let err = RedisCache::new("http://not-redis").unwrap_err();
+        assert!(matches!(err, RsshubError::InvalidConfig { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_clients_share_a_warm_cache() {
+        let (url, _store) = fake_redis().await;
+        let mut server = mockito::Server::new_async().await;
+        let namespace = server
+            .mock("GET", "/api/namespace/example")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"routes": {}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let replica = || {
+            crate::RsshubApiClient::builder()
+                .host(server.url())
+                .cache_backend(Arc::new(RedisCache::new(&url).unwrap()))
+                .build()
+                .unwrap()
+        };
+
+        replica().get_namespace("example").await.unwrap();
+        let second = replica();
+        second.get_namespace("example").await.unwrap();
+        namespace.assert_async().await;
+        assert_eq!(second.cache_stats().hits, 1);
+    }
+}
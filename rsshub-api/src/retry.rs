@@ -0,0 +1,137 @@
+//! Retry policy for upstream requests.
+
+use std::time::{Duration, SystemTime};
+
+use reqwest::{header::RETRY_AFTER, StatusCode};
+
+/// Controls how failed upstream requests are retried.
+///
+/// Transport failures, timeouts, `5xx` and `429` responses are retried; other `4xx`
+/// responses are returned immediately.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound for a single delay, including one requested via `Retry-After`
+    pub max_backoff: Duration,
+    /// Factor applied to the delay after every attempt
+    pub multiplier: f64,
+    /// Fraction of each delay that is randomized (0.0 = none, 1.0 = full jitter)
+    pub jitter: f64,
+    /// Overall budget for all attempts and delays of one request
+    pub deadline: Option<Duration>,
+    /// Use the upstream `Retry-After` header instead of the computed backoff when present
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(150),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.5,
+            deadline: Some(Duration::from_secs(180)),
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether a response with this status is worth another attempt.
+    pub fn is_retryable_status(status: StatusCode) -> bool {
+        status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+    }
+
+    /// Whether a transport error is worth another attempt.
+    pub fn is_retryable_error(err: &reqwest::Error) -> bool {
+        !err.is_builder() && !err.is_redirect()
+    }
+
+    /// Backoff before retry number `retry` (1-based), without jitter.
+    pub fn base_delay(&self, retry: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .max(1.0)
+            .powi(retry.saturating_sub(1) as i32);
+        let delay = self.initial_backoff.as_secs_f64() * factor;
+        Duration::from_secs_f64(delay.min(self.max_backoff.as_secs_f64()))
+    }
+
+    /// Backoff before retry number `retry` (1-based), with jitter applied.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let base = self.base_delay(retry);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        base.mul_f64(1.0 - jitter * fastrand::f64())
+    }
+
+    /// Delay requested by the upstream `Retry-After` header, capped at `max_backoff`.
+    pub(crate) fn retry_after(&self, response: &reqwest::Response) -> Option<Duration> {
+        if !self.respect_retry_after {
+            return None;
+        }
+        let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+        parse_retry_after(value, SystemTime::now()).map(|d| d.min(self.max_backoff))
+    }
+}
+
+/// Parse a `Retry-After` value given either as delta-seconds or as an HTTP date.
+pub(crate) fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = httpdate::parse_http_date(value).ok()?;
+    Some(at.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+            ..Default::default()
+        };
+        assert_eq!(policy.base_delay(1), Duration::from_millis(100));
+        assert_eq!(policy.base_delay(2), Duration::from_millis(200));
+        assert_eq!(policy.base_delay(3), Duration::from_millis(350));
+        for _ in 0..100 {
+            let d = policy.delay(2);
+            assert!(d >= Duration::from_millis(100) && d <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        assert_eq!(parse_retry_after("7", now), Some(Duration::from_secs(7)));
+        let later = httpdate::fmt_http_date(now + Duration::from_secs(30));
+        assert_eq!(
+            parse_retry_after(&later, now),
+            Some(Duration::from_secs(30))
+        );
+        let earlier = httpdate::fmt_http_date(now - Duration::from_secs(30));
+        assert_eq!(parse_retry_after(&earlier, now), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_only_server_errors_and_throttling_are_retryable() {
+        assert!(RetryPolicy::is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(RetryPolicy::is_retryable_status(
+            StatusCode::SERVICE_UNAVAILABLE
+        ));
+        assert!(RetryPolicy::is_retryable_status(
+            StatusCode::TOO_MANY_REQUESTS
+        ));
+        assert!(!RetryPolicy::is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!RetryPolicy::is_retryable_status(StatusCode::FORBIDDEN));
+    }
+}
@@ -0,0 +1,219 @@
+//! Best-effort repairs of malformed feed bodies: charset detection and transcoding,
+//! invalid XML characters and unescaped ampersands.
+
+use std::{borrow::Cow, ops::Range};
+
+use encoding_rs::{Encoding, UTF_8};
+
+/// How far into the body the XML declaration is looked for.
+const DECLARATION_SCAN_LEN: usize = 1024;
+
+/// Decode a feed body to UTF-8.
+///
+/// The charset comes from a byte order mark, then the `Content-Type` header, then the
+/// XML declaration. A body that is not valid UTF-8 although the header says so is decoded
+/// with the declared charset instead, as proxied sites often mislabel GBK or Big5. The
+/// declaration of a transcoded document is rewritten to UTF-8 so that the XML parsers do
+/// not decode it a second time.
+pub(crate) fn decode(
+    content_type: Option<&str>,
+    bytes: &[u8],
+    warnings: &mut Vec<String>,
+) -> String {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        return transcode(encoding, &bytes[bom_len..], warnings);
+    }
+    let header = content_type.and_then(|ct| charset_label(ct, warnings));
+    let declared = declared_charset(bytes).and_then(|label| lookup(&label, warnings));
+    let encoding = match (header, declared) {
+        (Some(header), Some(declared))
+            if header == UTF_8 && declared != UTF_8 && std::str::from_utf8(bytes).is_err() =>
+        {
+            warnings.push(format!(
+                "body is not valid UTF-8 as announced by Content-Type; decoded as {}",
+                declared.name()
+            ));
+            declared
+        }
+        (Some(header), _) => header,
+        (None, Some(declared)) => declared,
+        (None, None) => UTF_8,
+    };
+    transcode(encoding, bytes, warnings)
+}
+
+fn transcode(encoding: &'static Encoding, bytes: &[u8], warnings: &mut Vec<String>) -> String {
+    let (text, had_errors) = encoding.decode_without_bom_handling(bytes);
+    if had_errors {
+        warnings.push(format!(
+            "invalid {} byte sequences were replaced",
+            encoding.name()
+        ));
+    }
+    if encoding == UTF_8 {
+        text.into_owned()
+    } else {
+        declare_utf8(&text)
+    }
+}
+
+/// Encoding named by the `charset` parameter of a `Content-Type` value.
+fn charset_label(content_type: &str, warnings: &mut Vec<String>) -> Option<&'static Encoding> {
+    let label = content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"'))
+    })?;
+    lookup(label, warnings)
+}
+
+fn lookup(label: &str, warnings: &mut Vec<String>) -> Option<&'static Encoding> {
+    let encoding = Encoding::for_label(label.as_bytes());
+    if encoding.is_none() {
+        warnings.push(format!("unknown charset `{label}` ignored"));
+    }
+    encoding
+}
+
+/// The `encoding` pseudo-attribute of the XML declaration, if any.
+fn declared_charset(bytes: &[u8]) -> Option<String> {
+    // The declaration is ASCII in every charset feeds use
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(DECLARATION_SCAN_LEN)]);
+    let (_, value) = encoding_attr(&head)?;
+    Some(head[value].to_string())
+}
+
+/// Byte ranges of the whole `encoding="…"` pseudo-attribute of the XML declaration and
+/// of its value.
+fn encoding_attr(text: &str) -> Option<(Range<usize>, Range<usize>)> {
+    let start = text.find("<?xml")?;
+    let end = start + text[start..].find("?>")?;
+    let attr = start + text[start..end].find("encoding")?;
+    let rest = text[attr + "encoding".len()..end].trim_start();
+    let value = rest.strip_prefix('=')?.trim_start();
+    let quote = value.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+    let value_start = end - value.len() + 1;
+    let value_end = value_start + value[1..].find(quote)?;
+    Some((attr..value_end + 1, value_start..value_end))
+}
+
+/// `text` with the encoding of its XML declaration, if any, replaced by UTF-8.
+fn declare_utf8(text: &str) -> String {
+    match encoding_attr(text) {
+        Some((attr, _)) => format!(
+            "{}encoding=\"UTF-8\"{}",
+            &text[..attr.start],
+            &text[attr.end..]
+        ),
+        None => text.to_string(),
+    }
+}
+
+/// Whether XML 1.0 allows `c` in a document.
+fn is_xml_char(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+}
+
+/// `text` without the characters XML 1.0 forbids, such as stray control characters, and
+/// how many were removed.
+pub(crate) fn strip_invalid_xml_chars(text: &str) -> (Cow<'_, str>, usize) {
+    let invalid = text.chars().filter(|c| !is_xml_char(*c)).count();
+    if invalid == 0 {
+        return (Cow::Borrowed(text), 0);
+    }
+    (
+        Cow::Owned(text.chars().filter(|c| is_xml_char(*c)).collect()),
+        invalid,
+    )
+}
+
+/// `text` with every `&` outside CDATA sections that does not start an XML entity or
+/// character reference escaped, e.g. in unencoded query strings or HTML entities like
+/// `&nbsp;`, and how many were escaped.
+pub(crate) fn escape_bare_ampersands(text: &str) -> (String, usize) {
+    let mut out = String::with_capacity(text.len());
+    let mut escaped = 0;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (markup, cdata, next) = match rest.find("<![CDATA[") {
+            Some(start) => {
+                let end = rest[start..]
+                    .find("]]>")
+                    .map_or(rest.len(), |e| start + e + 3);
+                (&rest[..start], &rest[start..end], &rest[end..])
+            }
+            None => (rest, "", ""),
+        };
+        for (i, part) in markup.split('&').enumerate() {
+            if i > 0 {
+                if starts_with_reference(part) {
+                    out.push('&');
+                } else {
+                    out.push_str("&amp;");
+                    escaped += 1;
+                }
+            }
+            out.push_str(part);
+        }
+        out.push_str(cdata);
+        rest = next;
+    }
+    (out, escaped)
+}
+
+fn starts_with_reference(rest: &str) -> bool {
+    let Some((name, _)) = rest.split_once(';') else {
+        return false;
+    };
+    match name.strip_prefix('#') {
+        Some(hex) if hex.starts_with(['x', 'X']) => {
+            hex.len() > 1 && hex[1..].chars().all(|c| c.is_ascii_hexdigit())
+        }
+        Some(dec) => !dec.is_empty() && dec.chars().all(|c| c.is_ascii_digit()),
+        None => matches!(name, "amp" | "lt" | "gt" | "quot" | "apos"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_declared_charset_is_transcoded() {
+        let (bytes, _, _) =
+            encoding_rs::BIG5.encode("<?xml version='1.0' encoding='Big5'?><t>繁體</t>");
+        let mut warnings = Vec::new();
+        let text = decode(Some("application/xml"), &bytes, &mut warnings);
+        assert_eq!(text, "<?xml version='1.0' encoding=\"UTF-8\"?><t>繁體</t>");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_mislabelled_utf8_falls_back_to_declaration() {
+        let (bytes, _, _) =
+            encoding_rs::GBK.encode(r#"<?xml version="1.0" encoding="gb2312"?><t>中文</t>"#);
+        let mut warnings = Vec::new();
+        let text = decode(Some("text/xml; charset=utf-8"), &bytes, &mut warnings);
+        assert!(text.ends_with("<t>中文</t>"));
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+
+        let mut warnings = Vec::new();
+        decode(Some("text/xml; charset=klingon"), b"<t/>", &mut warnings);
+        assert_eq!(warnings, ["unknown charset `klingon` ignored"]);
+    }
+
+    #[test]
+    fn test_invalid_chars_and_ampersands_are_repaired() {
+        let (text, removed) = strip_invalid_xml_chars("a\u{0}b\u{b}c\td\u{FFFE}");
+        assert_eq!((text.as_ref(), removed), ("abc\td", 3));
+
+        let (text, escaped) =
+            escape_bare_ampersands("Q&A &amp; &#160; &#x1F600; &nbsp; <![CDATA[a&b]]> a&b=1");
+        assert_eq!(
+            text,
+            "Q&amp;A &amp; &#160; &#x1F600; &amp;nbsp; <![CDATA[a&b]]> a&amp;b=1"
+        );
+        assert_eq!(escaped, 3);
+    }
+}
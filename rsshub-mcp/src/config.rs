@@ -1,9 +1,11 @@
 #![allow(unused)]
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf, time::Duration};
 
+use chrono::FixedOffset;
 use clap::Parser;
 use config::{Config as FileConfig, ConfigError, Environment, File};
-use serde::Deserialize;
+use rsshub_api::{redact_url, AuthConfig, BodyLimits, FeedFormat, TransportConfig};
+use serde::{Deserialize, Deserializer};
 
 #[derive(Clone, Parser)]
 pub struct Cli {
@@ -13,9 +15,104 @@ pub struct Cli {
     pub version: bool,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct Config {
     pub sse_server_addr: String,
+    /// Directory for the persistent RSSHub response cache; in-memory only when unset
+    pub cache_dir: Option<PathBuf>,
+    /// Seconds past their TTL that cached responses may still be served while RSSHub is unreachable
+    pub max_stale_secs: Option<u64>,
+    /// Redis URL of a response cache shared by several replicas; needs the `redis` feature
+    pub cache_redis_url: Option<String>,
+    #[serde(default)]
+    pub rsshub: RsshubConfig,
+}
+
+// Manual so that a password in `cache_redis_url` stays out of logs
+impl std::fmt::Debug for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Config")
+            .field("sse_server_addr", &self.sse_server_addr)
+            .field("cache_dir", &self.cache_dir)
+            .field("max_stale_secs", &self.max_stale_secs)
+            .field(
+                "cache_redis_url",
+                &self.cache_redis_url.as_deref().map(redact_url),
+            )
+            .field("rsshub", &self.rsshub)
+            .finish()
+    }
+}
+
+/// `[rsshub]` section: upstream RSSHub instances
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RsshubConfig {
+    pub host: Option<String>,
+    /// Ordered instances, primary first; failed hosts are skipped for a cooldown
+    pub hosts: Option<Vec<String>>,
+    /// Ordered instances for feed requests; defaults to `hosts`
+    pub feed_hosts: Option<Vec<String>>,
+    /// Request timeout in seconds
+    pub timeout: Option<u64>,
+    /// Feed format requested from RSSHub: `rss`, `atom` or `json`; the route's default when unset
+    pub feed_format: Option<FeedFormat>,
+    /// UTC offset such as `+08:00` for feed dates written without a time zone; UTC when unset
+    #[serde(default, deserialize_with = "utc_offset")]
+    pub naive_date_offset: Option<FixedOffset>,
+    /// `[rsshub.auth]`: access key, basic auth or bearer token for protected instances
+    pub auth: Option<AuthConfig>,
+    /// `[rsshub.transport]`: user agent, headers, proxy and CA certificates
+    pub transport: Option<TransportSettings>,
+    /// `[rsshub.body_limits]`: maximum response size in bytes per endpoint
+    pub body_limits: Option<BodyLimits>,
+}
+
+fn utc_offset<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<FixedOffset>, D::Error> {
+    let Some(offset) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    offset
+        .parse()
+        .map(Some)
+        .map_err(|_| serde::de::Error::custom(format!("invalid UTC offset `{offset}`")))
+}
+
+/// `[rsshub.transport]` section; durations are given in seconds
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TransportSettings {
+    pub user_agent: Option<String>,
+    pub headers: HashMap<String, String>,
+    /// `http://`, `https://`, `socks5://` or `socks5h://` proxy URL
+    pub proxy: Option<String>,
+    pub no_proxy: Option<String>,
+    /// PEM files with additional root certificates
+    pub ca_certs: Vec<PathBuf>,
+    pub connect_timeout: Option<u64>,
+    pub pool_max_idle_per_host: Option<usize>,
+    pub pool_idle_timeout: Option<u64>,
+}
+
+// Delegates to `TransportConfig`, which keeps header values and proxy credentials out of logs
+impl std::fmt::Debug for TransportSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        TransportConfig::from(self.clone()).fmt(f)
+    }
+}
+
+impl From<TransportSettings> for TransportConfig {
+    fn from(settings: TransportSettings) -> Self {
+        Self {
+            user_agent: settings.user_agent,
+            default_headers: settings.headers,
+            proxy: settings.proxy,
+            no_proxy: settings.no_proxy,
+            ca_certs: settings.ca_certs,
+            connect_timeout: settings.connect_timeout.map(Duration::from_secs),
+            pool_max_idle_per_host: settings.pool_max_idle_per_host,
+            pool_idle_timeout: settings.pool_idle_timeout.map(Duration::from_secs),
+        }
+    }
 }
 
 impl Config {
@@ -2,10 +2,11 @@ mod config;
 mod log;
 mod service;
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use clap::Parser;
 use eyre::Result;
+use rsshub_api::{CacheBackend, CacheConfig, RsshubApiClientBuilder, RsshubClientConfig};
 use shadow_rs::shadow;
 use tracing::info;
 use ultrafast_mcp::{ServerCapabilities, ServerInfo, ToolsCapability, UltraFastServer};
@@ -49,7 +50,28 @@ async fn main() -> Result<()> {
     };
 
     // Create and configure the server
-    let rsshub_service = Arc::new(RSSHubService::new());
+    let client_config = RsshubClientConfig {
+        host: config.rsshub.host.clone(),
+        hosts: config.rsshub.hosts.clone(),
+        feed_hosts: config.rsshub.feed_hosts.clone(),
+        timeout: config.rsshub.timeout,
+        auth: config.rsshub.auth.clone(),
+        transport: config.rsshub.transport.clone().map(Into::into),
+        body_limits: config.rsshub.body_limits.clone(),
+        feed_format: config.rsshub.feed_format,
+        naive_date_offset: config.rsshub.naive_date_offset,
+        cache: Some(CacheConfig {
+            disk_dir: config.cache_dir.clone(),
+            max_stale: config.max_stale_secs.map(Duration::from_secs),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let mut builder = RsshubApiClientBuilder::from(client_config);
+    if let Some(url) = &config.cache_redis_url {
+        builder = builder.cache_backend(redis_cache(url)?);
+    }
+    let rsshub_service = Arc::new(RSSHubService::with_api(Arc::new(builder.build()?)));
     let server =
         UltraFastServer::new(server_info, capabilities).with_tool_handler(rsshub_service.clone());
 
@@ -65,3 +87,16 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Response cache shared with other replicas through Redis
+#[cfg(feature = "redis")]
+fn redis_cache(url: &str) -> Result<Arc<dyn CacheBackend>> {
+    Ok(Arc::new(rsshub_api::RedisCache::new(url)?))
+}
+
+#[cfg(not(feature = "redis"))]
+fn redis_cache(_url: &str) -> Result<Arc<dyn CacheBackend>> {
+    Err(eyre::eyre!(
+        "cache_redis_url is set, but rsshub-mcp was built without the `redis` feature"
+    ))
+}
@@ -1,7 +1,12 @@
-use std::sync::Arc;
+use std::{fmt, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
-use rsshub_api::{RsshubApiClient, RsshubClientConfig};
+use chrono::{DateTime, NaiveDate, Utc};
+use rsshub_api::{
+    parse_timestamp, ContentFormat, FeedChannel, FeedItem, FeedQuery, FeedResponse, Fetched,
+    OpenccConversion, ParseArgumentError, RsshubApi, RsshubApiClient, RsshubClientConfig,
+    RsshubError,
+};
 use serde_json::json;
 use tracing::info;
 use ultrafast_mcp::{
@@ -11,17 +16,21 @@ use ultrafast_mcp::{
 };
 
 /// RSSHub MCP Service that implements both ToolHandler and ResourceHandler
-#[derive(Debug)]
 pub struct RSSHubService {
-    client: Arc<RsshubApiClient>,
+    client: Arc<dyn RsshubApi>,
+}
+
+impl fmt::Debug for RSSHubService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RSSHubService").finish_non_exhaustive()
+    }
 }
 
 impl RSSHubService {
     /// Create a new RSSHubService with default configuration
-    pub fn new() -> Self {
-        let config = RsshubClientConfig::default();
-        let client = Arc::new(RsshubApiClient::new(config));
-        Self { client }
+    #[allow(dead_code)]
+    pub fn new() -> Result<Self, RsshubError> {
+        Self::with_config(RsshubClientConfig::default())
     }
 
     /// Handle search_routes tool call
@@ -53,10 +62,12 @@ impl RSSHubService {
         };
 
         let mut hits: Vec<serde_json::Value> = Vec::new();
+        let stale;
 
         if let Some(ns) = namespace {
-            let routes_map = self.client.get_namespace(ns).await?;
-            if let Some(routes) = routes_map.routes {
+            let fetched = self.client.fetch_namespace(ns).await?;
+            stale = stale_note(&fetched);
+            if let Some(routes) = fetched.value.routes {
                 for (key, details) in routes.iter() {
                     if matches(key, details) {
                         hits.push(serde_json::json!({
@@ -73,8 +84,9 @@ impl RSSHubService {
                 }
             }
         } else {
-            let all = self.client.get_all_namespaces().await?;
-            'outer: for (ns, routes_map) in all.iter() {
+            let all = self.client.fetch_all_namespaces().await?;
+            stale = stale_note(&all);
+            'outer: for (ns, routes_map) in all.value.iter() {
                 if let Some(routes) = routes_map.routes.as_ref() {
                     for (key, details) in routes.iter() {
                         if matches(key, details) {
@@ -94,10 +106,10 @@ impl RSSHubService {
             }
         }
 
-        if format.unwrap_or("text").eq_ignore_ascii_case("json") {
-            Ok(serde_json::to_string_pretty(&hits)?)
+        let out = if format.unwrap_or("text").eq_ignore_ascii_case("json") {
+            serde_json::to_string_pretty(&hits)?
         } else if hits.is_empty() {
-            Ok(format!("No route found matching '{query}'."))
+            format!("No route found matching '{query}'.")
         } else {
             let mut lines = Vec::new();
             lines.push(format!(
@@ -122,8 +134,9 @@ impl RSSHubService {
                     }
                 ));
             }
-            Ok(lines.join("\n"))
-        }
+            lines.join("\n")
+        };
+        Ok(with_note(stale, out))
     }
 
     /// Handle get_route_detail tool call
@@ -133,21 +146,21 @@ impl RSSHubService {
         route_key: &str,
         format: Option<&str>,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let routes_map = self.client.get_namespace(namespace).await?;
-        let Some(routes) = routes_map.routes else {
+        let fetched = self.client.fetch_namespace(namespace).await?;
+        let stale = stale_note(&fetched);
+        let Some(routes) = fetched.value.routes else {
             return Ok(format!("Namespace '{namespace}' has no routes."));
         };
-        if let Some(details) = routes.get(route_key) {
+        let out = if let Some(details) = routes.get(route_key) {
             if format.unwrap_or("text").eq_ignore_ascii_case("json") {
-                Ok(serde_json::to_string_pretty(details)?)
+                serde_json::to_string_pretty(details)?
             } else {
-                Ok(format!("{details:#?}"))
+                format!("{details:#?}")
             }
         } else {
-            Ok(format!(
-                "Route '{route_key}' not found in namespace '{namespace}'."
-            ))
-        }
+            format!("Route '{route_key}' not found in namespace '{namespace}'.")
+        };
+        Ok(with_note(stale, out))
     }
 
     /// Suggest closest route keys within a namespace
@@ -158,8 +171,9 @@ impl RSSHubService {
         limit: Option<usize>,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         let limit = limit.unwrap_or(10);
-        let routes_map = self.client.get_namespace(namespace).await?;
-        let Some(routes) = routes_map.routes else {
+        let fetched = self.client.fetch_namespace(namespace).await?;
+        let stale = stale_note(&fetched);
+        let Some(routes) = fetched.value.routes else {
             return Ok(format!("Namespace '{namespace}' has no routes."));
         };
         let p = partial.to_lowercase();
@@ -173,16 +187,22 @@ impl RSSHubService {
             (contains, starts, len_diff)
         });
         let list: Vec<String> = keys.into_iter().take(limit).cloned().collect();
-        Ok(format!(
+        let out = format!(
             "Suggested route keys (top {}):\n{}",
             list.len(),
             list.join("\n")
-        ))
+        );
+        Ok(with_note(stale, out))
     }
     /// Create a new RSSHubService with custom configuration
     #[allow(dead_code)]
-    pub fn with_config(config: RsshubClientConfig) -> Self {
-        let client = Arc::new(RsshubApiClient::new(config));
+    pub fn with_config(config: RsshubClientConfig) -> Result<Self, RsshubError> {
+        let client = Arc::new(RsshubApiClient::new(config)?);
+        Ok(Self::with_api(client))
+    }
+
+    /// Create a new RSSHubService on top of any RSSHub backend, e.g. a fake in tests
+    pub fn with_api(client: Arc<dyn RsshubApi>) -> Self {
         Self { client }
     }
 
@@ -190,8 +210,9 @@ impl RSSHubService {
     async fn handle_get_all_namespaces(
         &self,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let namespaces = self.client.get_all_namespaces().await?;
-        Ok(format!("{namespaces:#?}"))
+        let namespaces = self.client.fetch_all_namespaces().await?;
+        let out = format!("{:#?}", namespaces.value);
+        Ok(with_note(stale_note(&namespaces), out))
     }
 
     /// Handle get_namespace tool call
@@ -200,12 +221,13 @@ impl RSSHubService {
         namespace: &str,
         format: Option<&str>,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let routes = self.client.get_namespace(namespace).await?;
-        if format.unwrap_or("text").eq_ignore_ascii_case("json") {
-            Ok(serde_json::to_string_pretty(&routes)?)
+        let routes = self.client.fetch_namespace(namespace).await?;
+        let out = if format.unwrap_or("text").eq_ignore_ascii_case("json") {
+            serde_json::to_string_pretty(&routes.value)?
         } else {
-            Ok(format!("{routes:#?}"))
-        }
+            format!("{:#?}", routes.value)
+        };
+        Ok(with_note(stale_note(&routes), out))
     }
 
     /// Handle search_namespaces tool call - More useful than listing all
@@ -213,9 +235,11 @@ impl RSSHubService {
         &self,
         query: Option<&str>,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let all_namespaces = self.client.get_all_namespaces().await?;
+        let fetched = self.client.fetch_all_namespaces().await?;
+        let stale = stale_note(&fetched);
+        let all_namespaces = fetched.value;
 
-        if let Some(search_query) = query {
+        let out = if let Some(search_query) = query {
             // Filter namespaces that match the search query
             let search_lower = search_query.to_lowercase();
             let filtered: Vec<String> = all_namespaces
@@ -225,29 +249,30 @@ impl RSSHubService {
                 .collect();
 
             if filtered.is_empty() {
-                Ok(format!(
+                format!(
                     "No namespaces found matching '{search_query}'. Available namespaces: {}",
                     all_namespaces
                         .keys()
                         .cloned()
                         .collect::<Vec<String>>()
                         .join(", ")
-                ))
+                )
             } else {
-                Ok(format!(
+                format!(
                     "Namespaces matching '{search_query}':\n{}",
                     filtered.join("\n")
-                ))
+                )
             }
         } else {
             // Return a concise list of all namespaces
             let namespace_list: Vec<String> = all_namespaces.keys().cloned().collect();
-            Ok(format!(
+            format!(
                 "Available namespaces ({} total):\n{}",
                 namespace_list.len(),
                 namespace_list.join(", ")
-            ))
-        }
+            )
+        };
+        Ok(with_note(stale, out))
     }
 
     /// Handle get_radar_rules tool call
@@ -255,12 +280,13 @@ impl RSSHubService {
         &self,
         format: Option<&str>,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let rules = self.client.get_all_radar_rules().await?;
-        if format.unwrap_or("text").eq_ignore_ascii_case("json") {
-            Ok(serde_json::to_string_pretty(&rules)?)
+        let rules = self.client.fetch_all_radar_rules().await?;
+        let out = if format.unwrap_or("text").eq_ignore_ascii_case("json") {
+            serde_json::to_string_pretty(rules.value.as_ref())?
         } else {
-            Ok(format!("{rules:#?}"))
-        }
+            format!("{:#?}", rules.value)
+        };
+        Ok(with_note(stale_note(&rules), out))
     }
 
     /// Handle get_radar_rule tool call
@@ -269,12 +295,13 @@ impl RSSHubService {
         rule_name: &str,
         format: Option<&str>,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let rule = self.client.get_radar_rule(rule_name).await?;
-        if format.unwrap_or("text").eq_ignore_ascii_case("json") {
-            Ok(serde_json::to_string_pretty(&rule)?)
+        let rule = self.client.fetch_radar_rule(rule_name).await?;
+        let out = if format.unwrap_or("text").eq_ignore_ascii_case("json") {
+            serde_json::to_string_pretty(&rule.value)?
         } else {
-            Ok(format!("{rule:#?}"))
-        }
+            format!("{:#?}", rule.value)
+        };
+        Ok(with_note(stale_note(&rule), out))
     }
 
     /// Handle get_categories tool call
@@ -292,23 +319,30 @@ impl RSSHubService {
         category: &str,
         format: Option<&str>,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let category_items = self.client.get_category(category).await?;
-        if format.unwrap_or("text").eq_ignore_ascii_case("json") {
-            Ok(serde_json::to_string_pretty(&category_items)?)
+        let category_items = self.client.fetch_category(category).await?;
+        let out = if format.unwrap_or("text").eq_ignore_ascii_case("json") {
+            serde_json::to_string_pretty(&category_items.value)?
         } else {
-            Ok(format!("{category_items:#?}"))
-        }
+            format!("{:#?}", category_items.value)
+        };
+        Ok(with_note(stale_note(&category_items), out))
     }
 
     /// Handle get_feed tool call - Fetch actual RSS content
     async fn handle_get_feed(
         &self,
         path: &str,
+        query: &FeedQuery,
+        view: &FeedView,
         format: Option<&str>,
     ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-        let feed_response = self.client.get_feed(path).await?;
-        if format.unwrap_or("text").eq_ignore_ascii_case("json") {
-            Ok(serde_json::to_string_pretty(&feed_response)?)
+        let fetched = self.client.fetch_feed_with(path, query).await?;
+        let stale = stale_note(&fetched);
+        let source = fetched.host;
+        let mut feed_response = fetched.value;
+        view.apply(&mut feed_response);
+        let out = if format.unwrap_or("text").eq_ignore_ascii_case("json") {
+            serde_json::to_string_pretty(&feed_response)?
         } else {
             // Text summary
             let mut lines = Vec::new();
@@ -316,15 +350,34 @@ impl RSSHubService {
             if !feed_response.description.is_empty() {
                 lines.push(format!("Description: {}", feed_response.description));
             }
+            lines.extend(channel_details(&feed_response.channel));
             let show = feed_response.items.iter().take(3);
             for (idx, item) in show.enumerate() {
                 lines.push(format!("- {} {}", idx + 1, item.title));
+                let description = item.description.trim();
+                if !description.is_empty() {
+                    // Full text was asked for explicitly, so it is not cut
+                    let description = if query.is_fulltext() {
+                        description.to_string()
+                    } else {
+                        excerpt(description, DESCRIPTION_EXCERPT_LEN)
+                    };
+                    lines.push(format!("  {description}"));
+                }
+                lines.extend(item_details(item));
             }
             if feed_response.raw_content.is_some() {
                 lines.push("(raw content available)".to_string());
             }
-            Ok(lines.join("\n"))
-        }
+            for warning in &feed_response.warnings {
+                lines.push(format!("Warning: {warning}"));
+            }
+            if let Some(source) = source {
+                lines.push(format!("Source: {source}"));
+            }
+            lines.join("\n")
+        };
+        Ok(with_note(stale, out))
     }
 }
 
@@ -446,17 +499,7 @@ impl ToolHandler for RSSHubService {
                 description: "Fetch actual RSS feed content from a RSSHub path".to_string(),
                 annotations: None,
                 output_schema: None,
-                input_schema: json!({
-                    "type": "object",
-                    "properties": {
-                        "path": {
-                            "type": "string",
-                            "description": "The RSSHub path (e.g., 'bilibili/user/video/2267573', 'github/issue/DIYgod/RSSHub')"
-                        },
-                        "format": {"type": "string", "enum": ["text", "json"], "description": "Output format (default text)"}
-                    },
-                    "required": ["path"]
-                }),
+                input_schema: get_feed_schema(),
             },
             Tool {
                 name: "search_routes".to_string(),
@@ -613,7 +656,9 @@ impl ToolHandler for RSSHubService {
                     .as_ref()
                     .and_then(|args| args.get("format"))
                     .and_then(|v| v.as_str());
-                self.handle_get_feed(path, format).await
+                let query = feed_query(request.arguments.as_ref())?;
+                let view = feed_view(request.arguments.as_ref())?;
+                self.handle_get_feed(path, &query, &view, format).await
             }
             "search_routes" => {
                 let args = request.arguments.as_ref().ok_or_else(|| {
@@ -688,9 +733,696 @@ impl ToolHandler for RSSHubService {
                 is_error: Some(false),
             }),
             Err(e) => Ok(ToolCallResponse {
-                content: vec![ToolContent::text(format!("Error: {e}"))],
+                content: vec![ToolContent::text(describe_error(e.as_ref()))],
                 is_error: Some(true),
             }),
         }
     }
 }
+
+type FilterSetter = fn(FeedQuery, String) -> FeedQuery;
+
+/// RSSHub's generic filter parameters accepted by get_feed; all take a regular expression
+const FEED_FILTERS: [(&str, &str, FilterSetter); 10] = [
+    (
+        "filter",
+        "Keep items whose title or description matches",
+        |q, p| q.filter(p),
+    ),
+    ("filter_title", "Keep items whose title matches", |q, p| {
+        q.filter_title(p)
+    }),
+    (
+        "filter_description",
+        "Keep items whose description matches",
+        |q, p| q.filter_description(p),
+    ),
+    (
+        "filter_author",
+        "Keep items whose author matches",
+        |q, p| q.filter_author(p),
+    ),
+    (
+        "filter_category",
+        "Keep items with a matching category",
+        |q, p| q.filter_category(p),
+    ),
+    (
+        "filterout",
+        "Drop items whose title or description matches",
+        |q, p| q.filterout(p),
+    ),
+    (
+        "filterout_title",
+        "Drop items whose title matches",
+        |q, p| q.filterout_title(p),
+    ),
+    (
+        "filterout_description",
+        "Drop items whose description matches",
+        |q, p| q.filterout_description(p),
+    ),
+    (
+        "filterout_author",
+        "Drop items whose author matches",
+        |q, p| q.filterout_author(p),
+    ),
+    (
+        "filterout_category",
+        "Drop items with a matching category",
+        |q, p| q.filterout_category(p),
+    ),
+];
+
+/// Input schema of get_feed: the path plus RSSHub's generic feed parameters
+fn get_feed_schema() -> serde_json::Value {
+    let mut schema = json!({
+        "type": "object",
+        "properties": {
+            "path": {
+                "type": "string",
+                "description": "The RSSHub path (e.g., 'bilibili/user/video/2267573', 'github/issue/DIYgod/RSSHub')"
+            },
+            "format": {"type": "string", "enum": ["text", "json"], "description": "Output format (default text)"},
+            "limit": {"type": "integer", "minimum": 1, "description": "Maximum number of items"},
+            "filter_time": {"type": "integer", "minimum": 1, "description": "Only items published within this many seconds"},
+            "filter_case_sensitive": {"type": "boolean", "description": "Match filters case-sensitively (default true)"},
+            "sorted": {"type": "boolean", "description": "Sort items by date (default true); false keeps the route's order"},
+            "fulltext": {"type": "boolean", "description": "Fetch the full article content of each item instead of a summary"},
+            "brief": {"type": "integer", "minimum": 100, "description": "Reduce each item description to plain text of about this many characters"},
+            "opencc": {
+                "type": "string",
+                "enum": OpenccConversion::ALL.map(OpenccConversion::as_str),
+                "description": "Convert Chinese text, e.g. s2t (Simplified to Traditional) or t2s"
+            },
+            "since": {"type": "string", "description": "Only items dated at or after this time, e.g. 2025-08-12T00:00:00Z or 2025-08-12 (UTC unless a zone is given)"},
+            "until": {"type": "string", "description": "Only items dated at or before this time, in the same formats as since; a date alone includes that whole day"},
+            "order": {"type": "string", "enum": ["newest", "oldest"], "description": "Sort the returned items by their normalized date; undated items come last"},
+            "content_format": {
+                "type": "string",
+                "enum": ContentFormat::ALL.map(ContentFormat::as_str),
+                "description": "Item descriptions as the feed's HTML (default), Markdown or plain text; links and image alt text are kept, scripts, styles and tracking pixels removed"
+            }
+        },
+        "required": ["path"]
+    });
+    for (name, description, _) in FEED_FILTERS {
+        schema["properties"][name] = json!({
+            "type": "string",
+            "description": format!("{description} this regular expression")
+        });
+    }
+    schema
+}
+
+/// RSSHub's generic feed parameters from the get_feed arguments; others are ignored
+fn feed_query(args: Option<&serde_json::Value>) -> Result<FeedQuery, MCPError> {
+    let mut query = FeedQuery::new();
+    let Some(args) = args else {
+        return Ok(query);
+    };
+    if let Some(limit) = args.get("limit").and_then(|v| v.as_u64()) {
+        query = query.limit(limit as usize);
+    }
+    for (name, _, set) in FEED_FILTERS {
+        if let Some(pattern) = args.get(name).and_then(|v| v.as_str()) {
+            query = set(query, pattern.to_string());
+        }
+    }
+    if let Some(secs) = args.get("filter_time").and_then(|v| v.as_u64()) {
+        query = query.filter_time(Duration::from_secs(secs));
+    }
+    if let Some(case_sensitive) = args.get("filter_case_sensitive").and_then(|v| v.as_bool()) {
+        query = query.filter_case_sensitive(case_sensitive);
+    }
+    if let Some(sorted) = args.get("sorted").and_then(|v| v.as_bool()) {
+        query = query.sorted(sorted);
+    }
+    if let Some(fulltext) = args.get("fulltext").and_then(|v| v.as_bool()) {
+        query = query.fulltext(fulltext);
+    }
+    if let Some(chars) = args.get("brief").and_then(|v| v.as_u64()) {
+        if chars < 100 {
+            return Err(MCPError::invalid_params(
+                "brief must be at least 100".to_string(),
+            ));
+        }
+        query = query.brief(chars as usize);
+    }
+    if let Some(opencc) = args.get("opencc").and_then(|v| v.as_str()) {
+        let conversion = opencc
+            .parse()
+            .map_err(|e: ParseArgumentError| MCPError::invalid_params(e.to_string()))?;
+        query = query.opencc(conversion);
+    }
+    Ok(query)
+}
+
+/// Filtering, ordering and rendering of get_feed items, done locally after RSSHub's own
+/// parameters were applied
+#[derive(Debug, Default)]
+struct FeedView {
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    newest_first: Option<bool>,
+    content_format: ContentFormat,
+}
+
+impl FeedView {
+    fn apply(&self, feed: &mut FeedResponse) {
+        feed.retain_between(self.since, self.until);
+        if let Some(newest_first) = self.newest_first {
+            feed.sort_by_date(newest_first);
+        }
+        feed.convert_descriptions(self.content_format);
+    }
+}
+
+/// The since, until, order and content_format arguments of get_feed
+fn feed_view(args: Option<&serde_json::Value>) -> Result<FeedView, MCPError> {
+    let Some(args) = args else {
+        return Ok(FeedView::default());
+    };
+    let date = |name: &str| -> Result<Option<DateTime<Utc>>, MCPError> {
+        let Some(value) = args.get(name).and_then(|v| v.as_str()) else {
+            return Ok(None);
+        };
+        parse_timestamp(value).map(Some).ok_or_else(|| {
+            MCPError::invalid_params(format!("{name} is not a recognized date: {value}"))
+        })
+    };
+    let (since, mut until) = (date("since")?, date("until")?);
+    // A date alone includes that whole day
+    let until_day = args
+        .get("until")
+        .and_then(|v| v.as_str())
+        .and_then(|v| NaiveDate::parse_from_str(v.trim(), "%Y-%m-%d").ok());
+    if let Some(day) = until_day {
+        until = day
+            .succ_opt()
+            .and_then(|next| next.and_hms_opt(0, 0, 0))
+            .map(|midnight| midnight.and_utc() - chrono::Duration::nanoseconds(1));
+    }
+    if let (Some(since), Some(until)) = (since, until) {
+        if since > until {
+            return Err(MCPError::invalid_params(
+                "since must not be later than until".to_string(),
+            ));
+        }
+    }
+    let newest_first = match args.get("order").and_then(|v| v.as_str()) {
+        None => None,
+        Some("newest") => Some(true),
+        Some("oldest") => Some(false),
+        Some(other) => {
+            return Err(MCPError::invalid_params(format!(
+                "order must be newest or oldest, not {other}"
+            )))
+        }
+    };
+    let content_format = args
+        .get("content_format")
+        .and_then(|v| v.as_str())
+        .map(str::parse)
+        .transpose()
+        .map_err(|e: ParseArgumentError| MCPError::invalid_params(e.to_string()))?
+        .unwrap_or_default();
+    Ok(FeedView {
+        since,
+        until,
+        newest_first,
+        content_format,
+    })
+}
+
+/// Characters of an item description shown in text output, unless full text was requested
+const DESCRIPTION_EXCERPT_LEN: usize = 300;
+
+/// The first `max_chars` characters of `text`, marked with `…` when cut
+fn excerpt(text: &str, max_chars: usize) -> String {
+    let mut out: String = text.chars().take(max_chars).collect();
+    if out.len() < text.len() {
+        out.push('…');
+    }
+    out
+}
+
+/// Channel metadata useful to decide when to poll again, one line each
+fn channel_details(channel: &FeedChannel) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(link) = &channel.link {
+        lines.push(format!("Link: {link}"));
+    }
+    match (channel.last_build_at, &channel.last_build_date) {
+        (Some(date), _) => lines.push(format!("Updated: {date}")),
+        (None, Some(raw)) => lines.push(format!("Updated: {raw} (format not recognized)")),
+        (None, None) => {}
+    }
+    if let Some(ttl) = channel.ttl_minutes {
+        lines.push(format!("TTL: {ttl} minutes"));
+    }
+    if !channel.skip_hours.is_empty() {
+        let hours: Vec<String> = channel.skip_hours.iter().map(u8::to_string).collect();
+        lines.push(format!("Skip hours (GMT): {}", hours.join(", ")));
+    }
+    if !channel.skip_days.is_empty() {
+        lines.push(format!("Skip days: {}", channel.skip_days.join(", ")));
+    }
+    lines
+}
+
+/// Indented lines for the date, identifiers, attachments and podcast metadata of an item
+fn item_details(item: &FeedItem) -> Vec<String> {
+    let mut lines = Vec::new();
+    match item.timestamp() {
+        Some(date) => lines.push(format!("  Date: {date}")),
+        None => {
+            if let Some(raw) = item.pub_date.as_ref().or(item.updated.as_ref()) {
+                lines.push(format!("  Date: {raw} (format not recognized)"));
+            }
+        }
+    }
+    if let Some(guid) = item.guid.as_deref().filter(|guid| *guid != item.link) {
+        lines.push(format!("  GUID: {guid}"));
+    }
+    if let Some(podcast) = &item.podcast {
+        let mut parts = Vec::new();
+        match (&podcast.season, &podcast.episode) {
+            (Some(season), Some(episode)) => parts.push(format!("S{season}E{episode}")),
+            (None, Some(episode)) => parts.push(format!("episode {episode}")),
+            _ => {}
+        }
+        if let Some(secs) = podcast.duration_secs {
+            parts.push(format_duration(secs));
+        }
+        parts.extend(podcast.episode_type.clone());
+        if podcast.explicit == Some(true) {
+            parts.push("explicit".to_string());
+        }
+        if !parts.is_empty() {
+            lines.push(format!("  Podcast: {}", parts.join(", ")));
+        }
+    }
+    // BitTorrent enclosures are listed as magnet or torrent links
+    let bittorrent = [item.magnet.as_deref(), item.torrent.as_deref()];
+    for enclosure in &item.enclosures {
+        if bittorrent.contains(&Some(enclosure.url.as_str())) {
+            continue;
+        }
+        let details: Vec<String> = enclosure
+            .mime_type
+            .clone()
+            .into_iter()
+            .chain(enclosure.length.map(format_size))
+            .collect();
+        if details.is_empty() {
+            lines.push(format!("  Enclosure: {}", enclosure.url));
+        } else {
+            lines.push(format!(
+                "  Enclosure: {} ({})",
+                enclosure.url,
+                details.join(", ")
+            ));
+        }
+    }
+    let links = [
+        ("Magnet", &item.magnet),
+        ("Torrent", &item.torrent),
+        ("Image", &item.image),
+        ("Thumbnail", &item.thumbnail),
+        ("Comments", &item.comments),
+    ];
+    for (label, url) in links {
+        if let Some(url) = url {
+            lines.push(format!("  {label}: {url}"));
+        }
+    }
+    lines
+}
+
+/// `H:MM:SS`, or `M:SS` under an hour
+fn format_duration(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// Byte count in the largest binary unit that keeps it at or above 1
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Warning for data served from cache past its TTL
+fn stale_note<T>(fetched: &Fetched<T>) -> Option<String> {
+    fetched.stale.then(|| {
+        format!(
+            "Note: RSSHub could not confirm this data is current; it is served from cache and is {}s old, so it may be outdated.",
+            fetched.age.as_secs()
+        )
+    })
+}
+
+/// Prefix tool output with an optional note
+fn with_note(note: Option<String>, out: String) -> String {
+    match note {
+        Some(note) => format!("{note}\n{out}"),
+        None => out,
+    }
+}
+
+/// Turn a handler error into a message the model can act on
+fn describe_error(e: &(dyn std::error::Error + Send + Sync + 'static)) -> String {
+    match e.downcast_ref::<RsshubError>() {
+        Some(RsshubError::NotFound { url }) => format!(
+            "Error: not found upstream ({url}). Check the namespace, route key or path; \
+             search_routes and suggest_route_keys can help find valid ones."
+        ),
+        Some(RsshubError::Timeout { url }) => {
+            format!("Error: RSSHub did not answer in time ({url}). Try again later.")
+        }
+        Some(RsshubError::ResponseTooLarge { url, limit }) => format!(
+            "Error: the response from {url} is larger than the configured limit of {limit} bytes \
+             and was not read. Try a narrower route, or a smaller `limit` if the route supports one."
+        ),
+        Some(RsshubError::Http { status, .. }) if status.is_server_error() => {
+            format!("Error: the RSSHub instance is failing, the route may be broken upstream: {e}")
+        }
+        _ => format!("Error: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rsshub_api::{Enclosure, FakeRsshub, PodcastEpisode};
+
+    use super::*;
+
+    fn service() -> RSSHubService {
+        RSSHubService::with_api(Arc::new(FakeRsshub::from_fixtures()))
+    }
+
+    #[tokio::test]
+    async fn test_search_routes_within_namespace() {
+        let out = service()
+            .handle_search_routes("hot-list", Some("36kr"), None, Some("json"))
+            .await
+            .unwrap();
+        let hits: Vec<serde_json::Value> = serde_json::from_str(&out).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0]["namespace"], "36kr");
+        assert_eq!(hits[0]["route_key"], "/hot-list/:category?");
+        assert_eq!(hits[0]["name"], "资讯热榜");
+    }
+
+    #[tokio::test]
+    async fn test_search_routes_across_namespaces_respects_limit() {
+        let service = service();
+        let out = service
+            .handle_search_routes("hot", None, Some(2), Some("json"))
+            .await
+            .unwrap();
+        let hits: Vec<serde_json::Value> = serde_json::from_str(&out).unwrap();
+        assert_eq!(hits.len(), 2);
+
+        let out = service
+            .handle_search_routes("no-such-route-anywhere", None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(out, "No route found matching 'no-such-route-anywhere'.");
+    }
+
+    #[tokio::test]
+    async fn test_get_route_detail() {
+        let service = service();
+        let out = service
+            .handle_get_route_detail("36kr", "/hot-list/:category?", Some("json"))
+            .await
+            .unwrap();
+        let details: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(details["example"], "/36kr/hot-list");
+
+        let out = service
+            .handle_get_route_detail("36kr", "/missing", None)
+            .await
+            .unwrap();
+        assert_eq!(out, "Route '/missing' not found in namespace '36kr'.");
+
+        let err = service
+            .handle_get_route_detail("no-such-namespace", "/", None)
+            .await
+            .unwrap_err();
+        assert!(describe_error(err.as_ref()).starts_with("Error: not found upstream"));
+    }
+
+    #[test]
+    fn test_feed_arguments_become_a_query() {
+        let args = json!({
+            "path": "ithome/it",
+            "limit": 5,
+            "filter_title": "Rust",
+            "filterout_author": "bot",
+            "filter_time": 3600,
+            "sorted": false,
+            "unknown": "ignored"
+        });
+        let query = feed_query(Some(&args)).unwrap();
+        assert_eq!(
+            query,
+            FeedQuery::new()
+                .limit(5)
+                .filter_title("Rust")
+                .filterout_author("bot")
+                .filter_time(Duration::from_secs(3600))
+                .sorted(false)
+        );
+        assert!(feed_query(None).unwrap().is_empty());
+
+        let args = json!({"fulltext": true, "brief": 150, "opencc": "s2t"});
+        assert_eq!(
+            feed_query(Some(&args)).unwrap(),
+            FeedQuery::new()
+                .fulltext(true)
+                .brief(150)
+                .opencc(OpenccConversion::S2t)
+        );
+        let err = feed_query(Some(&json!({"opencc": "x2y"}))).unwrap_err();
+        assert!(err.to_string().contains("unknown OpenCC conversion `x2y`"));
+        assert!(!err.to_string().contains("configuration"));
+        assert!(feed_query(Some(&json!({"brief": 10}))).is_err());
+
+        let schema = get_feed_schema();
+        for (name, _, _) in FEED_FILTERS {
+            assert_eq!(schema["properties"][name]["type"], "string");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_feed_with_query() {
+        let service = service();
+        let query = FeedQuery::new().limit(2);
+        let out = service
+            .handle_get_feed("ithome/it", &query, &FeedView::default(), None)
+            .await
+            .unwrap();
+        assert!(out.starts_with("RSS Feed: IT之家"));
+        for line in [
+            "Link: https://www.ithome.com/",
+            "Updated: 2024-08-13 08:30:00 UTC",
+            "TTL: 5 minutes",
+            "Skip hours (GMT): 18, 19",
+            "Skip days: Sunday",
+        ] {
+            assert!(out.lines().any(|l| l == line), "{line} missing from {out}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fulltext_descriptions_are_not_cut() {
+        let fake = FakeRsshub::from_fixtures();
+        let mut feed = fake.fetch_feed("ithome/it").await.unwrap().value;
+        feed.items[0].description = "正文".repeat(400);
+        let service = RSSHubService::with_api(Arc::new(fake.with_feed("ithome/it", feed)));
+
+        let summary = service
+            .handle_get_feed("ithome/it", &FeedQuery::new(), &FeedView::default(), None)
+            .await
+            .unwrap();
+        let cut = format!("  {}…", "正文".repeat(DESCRIPTION_EXCERPT_LEN / 2));
+        assert!(summary.lines().any(|line| line == cut));
+
+        let full = service
+            .handle_get_feed(
+                "ithome/it",
+                &FeedQuery::new().fulltext(true),
+                &FeedView::default(),
+                None,
+            )
+            .await
+            .unwrap();
+        let whole = format!("  {}", "正文".repeat(400));
+        assert!(full.lines().any(|line| line == whole));
+    }
+
+    #[tokio::test]
+    async fn test_items_are_filtered_and_ordered_by_date() {
+        let args = json!({"since": "2024-08-13T08:00:00Z", "order": "oldest"});
+        let view = feed_view(Some(&args)).unwrap();
+        let out = service()
+            .handle_get_feed("ithome/it", &FeedQuery::new(), &view, None)
+            .await
+            .unwrap();
+        assert!(out.contains("- 1 First headline"));
+        assert!(out.contains("  Date: 2024-08-13 08:20:00 UTC"));
+        assert!(!out.contains("Second headline"));
+
+        let view = feed_view(Some(&json!({"order": "oldest"}))).unwrap();
+        let out = service()
+            .handle_get_feed("ithome/it", &FeedQuery::new(), &view, None)
+            .await
+            .unwrap();
+        assert!(out.contains("- 1 Second headline"));
+        assert!(out.contains("- 2 First headline"));
+
+        assert!(feed_view(Some(&json!({"since": "last week"}))).is_err());
+        assert!(feed_view(Some(&json!({"order": "random"}))).is_err());
+        let reversed = json!({"since": "2024-08-14", "until": "2024-08-13"});
+        assert!(feed_view(Some(&reversed)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_date_only_until_includes_that_day() {
+        let args = json!({"since": "2024-08-13", "until": "2024-08-13"});
+        let view = feed_view(Some(&args)).unwrap();
+        let out = service()
+            .handle_get_feed("ithome/it", &FeedQuery::new(), &view, None)
+            .await
+            .unwrap();
+        assert!(out.contains("First headline"));
+        assert!(out.contains("Second headline"));
+
+        let view = feed_view(Some(&json!({"until": "2024-08-12"}))).unwrap();
+        let out = service()
+            .handle_get_feed("ithome/it", &FeedQuery::new(), &view, None)
+            .await
+            .unwrap();
+        assert!(!out.contains("headline"));
+    }
+
+    #[tokio::test]
+    async fn test_descriptions_are_converted() {
+        let fake = FakeRsshub::from_fixtures();
+        let mut feed = fake.fetch_feed("ithome/it").await.unwrap().value;
+        feed.items[0].description = r#"<p>Read <a href="https://www.ithome.com/0/873/001.htm">more</a><img src="https://t.example/p.gif" width="1" height="1"></p><script>track()</script>"#.to_string();
+        let service = RSSHubService::with_api(Arc::new(fake.with_feed("ithome/it", feed)));
+
+        let view = feed_view(Some(&json!({"content_format": "markdown"}))).unwrap();
+        let json = service
+            .handle_get_feed("ithome/it", &FeedQuery::new(), &view, Some("json"))
+            .await
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value["items"][0]["description"],
+            "Read [more](https://www.ithome.com/0/873/001.htm)"
+        );
+
+        let view = feed_view(Some(&json!({"content_format": "text"}))).unwrap();
+        let text = service
+            .handle_get_feed("ithome/it", &FeedQuery::new(), &view, None)
+            .await
+            .unwrap();
+        assert!(text
+            .lines()
+            .any(|line| line == "  Read more (https://www.ithome.com/0/873/001.htm)"));
+
+        assert!(feed_view(Some(&json!({"content_format": "pdf"}))).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_podcast_and_bittorrent_details_are_listed() {
+        let fake = FakeRsshub::from_fixtures();
+        let mut feed = fake.fetch_feed("ithome/it").await.unwrap().value;
+        feed.items[0] = FeedItem {
+            title: "S8E12 | 开源大模型的下半场".to_string(),
+            link: "https://www.xiaoyuzhoufm.com/episode/66b0d3c2".to_string(),
+            guid: Some("66b0d3c2".to_string()),
+            enclosures: vec![Enclosure {
+                url: "https://media.xyzcdn.net/66b0d3c2.m4a".to_string(),
+                mime_type: Some("audio/x-m4a".to_string()),
+                length: Some(48213377),
+            }],
+            podcast: Some(PodcastEpisode {
+                duration_secs: Some(3723),
+                episode: Some("12".to_string()),
+                season: Some("8".to_string()),
+                ..PodcastEpisode::default()
+            }),
+            ..FeedItem::default()
+        };
+        feed.items[1] = FeedItem {
+            title: "Ubuntu 24.04".to_string(),
+            enclosures: vec![Enclosure {
+                url: "magnet:?xt=urn:btih:0123456789abcdef".to_string(),
+                mime_type: Some("application/x-bittorrent".to_string()),
+                length: None,
+            }],
+            magnet: Some("magnet:?xt=urn:btih:0123456789abcdef".to_string()),
+            ..FeedItem::default()
+        };
+        let service = RSSHubService::with_api(Arc::new(fake.with_feed("ithome/it", feed)));
+
+        let summary = service
+            .handle_get_feed("ithome/it", &FeedQuery::new(), &FeedView::default(), None)
+            .await
+            .unwrap();
+        let lines: Vec<&str> = summary.lines().collect();
+        assert!(lines.contains(&"  GUID: 66b0d3c2"));
+        assert!(lines.contains(&"  Podcast: S8E12, 1:02:03"));
+        assert!(lines.contains(
+            &"  Enclosure: https://media.xyzcdn.net/66b0d3c2.m4a (audio/x-m4a, 46.0 MiB)"
+        ));
+        assert!(lines.contains(&"  Magnet: magnet:?xt=urn:btih:0123456789abcdef"));
+        assert!(!summary.contains("Enclosure: magnet:"));
+
+        let json = service
+            .handle_get_feed(
+                "ithome/it",
+                &FeedQuery::new(),
+                &FeedView::default(),
+                Some("json"),
+            )
+            .await
+            .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["items"][0]["podcast"]["duration_secs"], 3723);
+        assert_eq!(value["items"][0]["enclosures"][0]["length"], 48213377);
+        assert_eq!(
+            value["items"][1]["magnet"],
+            "magnet:?xt=urn:btih:0123456789abcdef"
+        );
+    }
+
+    #[test]
+    fn test_oversized_response_is_explained() {
+        let err = RsshubError::ResponseTooLarge {
+            url: "https://rsshub.example/huge/feed".to_string(),
+            limit: 1024,
+        };
+        let message = describe_error(&err);
+        assert!(message.contains("https://rsshub.example/huge/feed"));
+        assert!(message.contains("limit of 1024 bytes"));
+    }
+}